@@ -90,6 +90,17 @@ impl Error {
         false
     }
 
+    /// Returns the ICU error code carried by this error, if any.
+    ///
+    /// Errors originating from the wrapper code do not carry an ICU error code, unless they wrap
+    /// an ICU error to add context to it, in which case the wrapped error's code is returned.
+    pub fn error_code(&self) -> Option<sys::UErrorCode> {
+        match self {
+            Error::Sys(code) => Some(*code),
+            Error::Wrapper(e) => e.downcast_ref::<Error>().and_then(Error::error_code),
+        }
+    }
+
     /// Returns true if the error is an error, not a warning.
     ///
     /// The ICU4C library has error codes for errors and warnings.
@@ -513,6 +524,16 @@ mod tests {
         assert!(error.is_code(sys::UErrorCode::U_BUFFER_OVERFLOW_ERROR));
         assert!(!error.is_preflight_err());
         assert!(!error.is_code(sys::UErrorCode::U_ZERO_ERROR));
+        assert_eq!(
+            error.error_code(),
+            Some(sys::UErrorCode::U_BUFFER_OVERFLOW_ERROR)
+        );
+        assert_eq!(Error::wrapper(anyhow!("wrapped")).error_code(), None);
+        let with_context = Error::wrapper(anyhow::Error::new(error).context("with context"));
+        assert_eq!(
+            with_context.error_code(),
+            Some(sys::UErrorCode::U_BUFFER_OVERFLOW_ERROR)
+        );
     }
 
     #[test]
@@ -33,21 +33,29 @@ OUTPUT_DIR=${OUTPUT_DIR:-.}
 # intended to be kept in sync with the static variable by the same name in the
 # build.rs file.
 readonly BINDGEN_SOURCE_MODULES=(
+        "ubidi"
         "ubrk"
         "ucal"
         "uclean"
         "ucol"
+        "ucurr"
         "udat"
         "udata"
+        "udatpg"
         "uenum"
         "ufieldpositer"
         "uformattable"
+        "uldnames"
         "ulistformatter"
         "umisc"
         "umsg"
         "unum"
         "unumberformatter"
+        "unumsys"
         "upluralrules"
+        "uregion"
+        "ures"
+        "uscript"
         "uset"
         "ustring"
         "utext"
@@ -59,6 +67,7 @@ readonly BINDGEN_SOURCE_MODULES=(
 # variable by the same name in the build.rs file.
 readonly BINDGEN_ALLOWLIST_TYPES=(
         "UAcceptResult"
+        "UBiDi.*"
         "UBool"
         "UBreakIterator"
         "UBreakIteratorType"
@@ -67,9 +76,11 @@ readonly BINDGEN_ALLOWLIST_TYPES=(
         "UCol.*"
         "UCollation.*"
         "UCollator"
+        "UCurr.*"
         "UData.*"
         "UDate.*"
         "UDateFormat.*"
+        "UDialectHandling"
         "UDisplayContext.*"
         "UEnumeration.*"
         "UErrorCode"
@@ -82,9 +93,13 @@ readonly BINDGEN_ALLOWLIST_TYPES=(
         "ULoc.*"
         "UMessageFormat"
         "UNUM.*"
-        "UNumber.*",
+        "UNumber.*"
         "UParseError"
         "UPlural.*"
+        "URegion.*"
+        "UResourceBundle"
+        "UScriptCode"
+        "UScriptUsage"
         "USentenceBreakTag"
         "USet"
         "UText"
@@ -99,20 +114,28 @@ readonly BINDGEN_ALLOWLIST_TYPES=(
 # the same name in the build.rs file.
 readonly BINDGEN_ALLOWLIST_FUNCTIONS=(
         "u_.*"
+        "ubidi_.*"
         "ubrk_.*"
         "ucal_.*"
         "ucol_.*"
+        "ucurr_.*"
         "udat_.*"
         "udata_.*"
+        "udatpg_.*"
         "uenum_.*"
         "ufieldpositer_.*"
         "ufmt_.*"
+        "uldn_.*"
         "ulistfmt_.*"
         "uloc_.*"
         "umsg_.*"
         "unum_.*"
         "unumf_.*"
+        "unumsys_.*"
         "uplrules_.*"
+        "uregion_.*"
+        "ures_.*"
+        "uscript_.*"
         "utext_.*"
         "utrans_.*"
 )
@@ -37,21 +37,29 @@ mod inner {
         // should be topologicaly sorted based on the inclusion relationship between the respective
         // headers.  Any of these will fail if the required binaries are not present in $PATH.
         static ref BINDGEN_SOURCE_MODULES: Vec<&'static str> = vec![
+            "ubidi",
             "ubrk",
             "ucal",
             "uclean",
             "ucol",
+            "ucurr",
             "udat",
             "udata",
+            "udatpg",
             "uenum",
             "ufieldpositer",
             "uformattable",
+            "uldnames",
             "ulistformatter",
             "umisc",
             "umsg",
             "unum",
             "unumberformatter",
+            "unumsys",
             "upluralrules",
+            "uregion",
+            "ures",
+            "uscript",
             "uset",
             "ustring",
             "utext",
@@ -62,20 +70,28 @@ mod inner {
         // bring in more types.
         static ref BINDGEN_ALLOWLIST_FUNCTIONS: Vec<&'static str> = vec![
             "u_.*",
+            "ubidi_.*",
             "ubrk_.*",
             "ucal_.*",
             "ucol_.*",
+            "ucurr_.*",
             "udat_.*",
             "udata_.*",
+            "udatpg_.*",
             "uenum_.*",
             "ufieldpositer_.*",
             "ufmt_.*",
+            "uldn_.*",
             "ulistfmt_.*",
             "uloc_.*",
             "umsg_.*",
             "unum_.*",
             "unumf_.*",
+            "unumsys_.*",
             "uplrules_.*",
+            "uregion_.*",
+            "ures_.*",
+            "uscript_.*",
             "utext_.*",
             "utrans_.*",
         ];
@@ -84,6 +100,7 @@ mod inner {
         // generate more bindings.
         static ref BINDGEN_ALLOWLIST_TYPES: Vec<&'static str> = vec![
             "UAcceptResult",
+            "UBiDi.*",
             "UBool",
             "UBreakIterator",
             "UBreakIteratorType",
@@ -92,9 +109,11 @@ mod inner {
             "UCol.*",
             "UCollation.*",
             "UCollator",
+            "UCurr.*",
             "UData.*",
             "UDate.*",
             "UDateFormat.*",
+            "UDialectHandling",
             "UDisplayContext.*",
             "UEnumeration.*",
             "UErrorCode",
@@ -110,6 +129,10 @@ mod inner {
             "UNumber.*",
             "UParseError",
             "UPlural.*",
+            "URegion.*",
+            "UResourceBundle",
+            "UScriptCode",
+            "UScriptUsage",
             "USentenceBreakTag",
             "USet",
             "UText",
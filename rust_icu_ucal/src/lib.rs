@@ -191,6 +191,30 @@ impl UCalendar {
         Ok(in_daylight_time != 0)
     }
 
+    /// Returns the value of the calendar attribute `attr`, such as the first day of the week.
+    ///
+    /// Implements `ucal_getAttribute`.
+    pub fn get_attribute(&self, attr: UCalendarAttribute) -> i32 {
+        unsafe { versioned_function!(ucal_getAttribute)(self.as_c_calendar(), attr) }
+    }
+
+    /// Returns the locale of the given type for this calendar, such as the locale its data
+    /// actually came from.
+    ///
+    /// Implements `ucal_getLocaleByType`.
+    pub fn get_locale_by_type(&self, type_: ULocDataLocaleType) -> Result<String, common::Error> {
+        let mut status = common::Error::OK_CODE;
+        let locale = unsafe {
+            versioned_function!(ucal_getLocaleByType)(self.as_c_calendar(), type_, &mut status)
+        };
+        common::Error::ok_or_warning(status)?;
+        if locale.is_null() {
+            return Ok(String::new());
+        }
+        let locale = unsafe { ffi::CStr::from_ptr(locale) };
+        Ok(locale.to_str()?.to_string())
+    }
+
     /// Implements `ucal_get`.
     ///
     /// Consider using specific higher-level methods instead.
@@ -410,6 +434,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_get_attribute() -> Result<(), common::Error> {
+        let cal = UCalendar::new("UTC", "en-US", UCalendarType::UCAL_DEFAULT)?;
+        assert_eq!(
+            cal.get_attribute(UCalendarAttribute::UCAL_FIRST_DAY_OF_WEEK),
+            UCalendarDaysOfWeek::UCAL_SUNDAY as i32
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_locale_by_type() -> Result<(), common::Error> {
+        let cal = UCalendar::new("UTC", "de_AT", UCalendarType::UCAL_DEFAULT)?;
+        assert_eq!(
+            cal.get_locale_by_type(ULocDataLocaleType::ULOC_VALID_LOCALE)?,
+            "de_AT"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_set_date() -> Result<(), common::Error> {
         // Timestamps hard-coded, not parsed, to avoid cyclic dependency on udat.
@@ -187,6 +187,102 @@ impl UCollator {
         unsafe { versioned_function!(ucol_setStrength)(self.rep.as_ptr(), strength) };
     }
 
+    /// Returns the sort key of `source`.
+    ///
+    /// Comparing the sort keys of two strings byte by byte gives the same result as comparing
+    /// the strings with [UCollator::strcoll], which is faster when strings are compared many
+    /// times.  The key includes its terminating zero byte.
+    ///
+    /// Implements `ucol_getSortKey`
+    pub fn get_sort_key(&self, source: &ustring::UChar) -> Vec<u8> {
+        let mut key: Vec<u8> = vec![0; 64];
+        // Unsafety note: the length passed in is that of the buffer.
+        let mut full_len = unsafe {
+            assert!(source.len() <= i32::MAX as usize);
+            versioned_function!(ucol_getSortKey)(
+                self.rep.as_ptr(),
+                source.as_c_ptr(),
+                source.len() as i32,
+                key.as_mut_ptr(),
+                key.len() as i32,
+            )
+        } as usize;
+        if full_len > key.len() {
+            key.resize(full_len, 0);
+            full_len = unsafe {
+                versioned_function!(ucol_getSortKey)(
+                    self.rep.as_ptr(),
+                    source.as_c_ptr(),
+                    source.len() as i32,
+                    key.as_mut_ptr(),
+                    key.len() as i32,
+                )
+            } as usize;
+        }
+        key.truncate(full_len);
+        key
+    }
+
+    /// Returns the locale of the given type for this collator, such as the locale its data
+    /// actually came from.
+    ///
+    /// Implements `ucol_getLocaleByType`
+    pub fn get_locale_by_type(
+        &self,
+        type_: sys::ULocDataLocaleType,
+    ) -> Result<String, common::Error> {
+        let mut status = common::Error::OK_CODE;
+        let locale = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(ucol_getLocaleByType)(self.rep.as_ptr(), type_, &mut status)
+        };
+        common::Error::ok_or_warning(status)?;
+        if locale.is_null() {
+            return Ok(String::new());
+        }
+        let locale = unsafe { ffi::CStr::from_ptr(locale) };
+        Ok(locale.to_str()?.to_string())
+    }
+
+    /// Returns the script reordering codes of this collator, as `UScriptCode` or
+    /// `UColReorderCode` values.
+    ///
+    /// The result is empty if the collator does not reorder scripts.
+    ///
+    /// Implements `ucol_getReorderCodes`
+    pub fn get_reorder_codes(&self) -> Result<Vec<i32>, common::Error> {
+        let mut status = common::Error::OK_CODE;
+        // Preflight to find out how many codes there are.
+        let len = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(ucol_getReorderCodes)(
+                self.rep.as_ptr(),
+                ptr::null_mut(),
+                0,
+                &mut status,
+            )
+        };
+        if status != sys::UErrorCode::U_BUFFER_OVERFLOW_ERROR {
+            common::Error::ok_or_warning(status)?;
+        }
+        let mut codes = vec![0i32; len as usize];
+        if codes.is_empty() {
+            return Ok(codes);
+        }
+        status = common::Error::OK_CODE;
+        unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(ucol_getReorderCodes)(
+                self.rep.as_ptr(),
+                codes.as_mut_ptr(),
+                len,
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        Ok(codes)
+    }
+
     // Implement `ucol_setAttribute`
     generalized_fallible_setter!(
         set_attribute,
@@ -264,6 +360,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn sort_key() -> Result<(), common::Error> {
+        let collator = crate::UCollator::try_from("en")?;
+        let key = |s: &str| collator.get_sort_key(&ustring::UChar::try_from(s).unwrap());
+        assert!(key("a") < key("b"));
+        assert_eq!(key("a").last(), Some(&0));
+        let long = "abcdefghijklmnopqrstuvwxyz".repeat(10);
+        assert!(key(&long) < key("b"));
+        Ok(())
+    }
+
+    #[test]
+    fn locale_by_type() -> Result<(), common::Error> {
+        let collator = crate::UCollator::try_from("de_AT")?;
+        assert_eq!(
+            collator.get_locale_by_type(sys::ULocDataLocaleType::ULOC_VALID_LOCALE)?,
+            "de_AT"
+        );
+        assert_eq!(
+            collator.get_locale_by_type(sys::ULocDataLocaleType::ULOC_ACTUAL_LOCALE)?,
+            "root"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reorder_codes() -> Result<(), common::Error> {
+        let collator = crate::UCollator::try_from("en")?;
+        assert_eq!(collator.get_reorder_codes()?, Vec::<i32>::new());
+        let collator = crate::UCollator::try_from("en-u-kr-grek-latn")?;
+        assert_eq!(
+            collator.get_reorder_codes()?,
+            vec![
+                sys::UScriptCode::USCRIPT_GREEK as i32,
+                sys::UScriptCode::USCRIPT_LATIN as i32
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn attribute_setter() {
         let collator = crate::UCollator::try_from("sr-Latn").unwrap();
@@ -45,6 +45,19 @@ impl Enumeration {
     pub fn empty() -> Self {
         Enumeration::try_from(&vec![][..]).unwrap()
     }
+
+    /// Rewinds the enumeration to its first element, so that it can be iterated over again.
+    ///
+    /// This allows an enumeration that is expensive to obtain to be kept and reused.
+    ///
+    /// Implements `uenum_reset`
+    pub fn reset(&mut self) -> Result<(), common::Error> {
+        let mut status = common::Error::OK_CODE;
+        // Requires that self.rep is a valid pointer to a sys::UEnumeration.
+        assert!(!self.rep.is_null());
+        unsafe { versioned_function!(uenum_reset)(self.rep, &mut status) };
+        common::Error::ok_or_warning(status)
+    }
 }
 
 impl Default for Enumeration {
@@ -269,6 +282,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reset() {
+        let mut e = Enumeration::try_from(&vec!["hello", "world"][..]).expect("enumeration?");
+        let first: Vec<String> = e.by_ref().map(|r| r.expect("no error")).collect();
+        assert_eq!(first, vec!["hello", "world"]);
+        assert_eq!(e.next().map(|r| r.expect("no error")), None);
+
+        e.reset().expect("reset");
+        let second: Vec<String> = e.by_ref().map(|r| r.expect("no error")).collect();
+        assert_eq!(second, first);
+    }
+
     #[test]
     fn error() {
         // A mutilated sparkle heart from https://doc.rust-lang.org/std/str/fn.from_utf8_unchecked.html
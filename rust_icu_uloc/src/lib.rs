@@ -13,17 +13,27 @@
 // limitations under the License.
 
 use {
+    anyhow::anyhow,
+    log::trace,
     rust_icu_common as common,
     rust_icu_common::buffered_string_method_with_retry,
     rust_icu_sys as sys,
     rust_icu_sys::versioned_function,
     rust_icu_sys::*,
+    rust_icu_ucal as ucal, rust_icu_ucol as ucol,
     rust_icu_uenum::Enumeration,
+    rust_icu_ulistformatter as ulistformatter, rust_icu_upluralrules as upluralrules,
+    rust_icu_ustring as ustring,
+    rust_icu_ustring::buffered_uchar_method_with_retry,
+    rust_icu_utrans as utrans,
     std::{
+        borrow::Cow,
         cmp::Ordering,
+        collections::{BTreeMap, BTreeSet, HashMap},
         convert::{From, TryFrom, TryInto},
         ffi, fmt,
         os::raw,
+        ptr,
     },
 };
 
@@ -31,18 +41,370 @@ use {
 /// See `ULOC_FULLNAME_CAPACITY`.
 const LOCALE_CAPACITY: usize = 158;
 
+/// A cache of canonicalized locale IDs, keyed by the string they were canonicalized from.
+///
+/// The cache holds at most [CAPACITY](canonicalize_cache::CAPACITY) entries; when it is full, it
+/// is emptied before the next entry is added.  Memory use is therefore bounded by that many pairs
+/// of short strings, and a workload cycling through more distinct tags than that simply gets
+/// fewer hits.
+#[cfg(feature = "canonicalize_cache")]
+mod canonicalize_cache {
+    use std::{collections::HashMap, sync::Mutex};
+
+    /// The maximum number of entries in the cache.
+    pub const CAPACITY: usize = 256;
+
+    static CACHE: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+    /// Returns the cached canonical form of `id`, if any.
+    pub fn get(id: &str) -> Option<String> {
+        let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        cache.as_ref().and_then(|cache| cache.get(id).cloned())
+    }
+
+    /// Records `canonical` as the canonical form of `id`.
+    pub fn insert(id: &str, canonical: &str) {
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        let cache = cache.get_or_insert_with(HashMap::new);
+        if cache.len() >= CAPACITY {
+            cache.clear();
+        }
+        cache.insert(id.to_string(), canonical.to_string());
+    }
+}
+
+/// Deprecated locale IDs that ICU does not replace when canonicalizing, by language and, where
+/// it decides the deprecation, variant.
+///
+/// From the CLDR language aliases, for `sh` (now `sr_Latn`) and `no_NO_NY` (now `nn_NO`).
+static DEPRECATED_IDS: &[(&str, Option<&str>)] = &[("sh", None), ("no", Some("NY"))];
+
+/// Traditional charsets by language and, where it decides the charset, script.
+///
+/// The first entry whose language matches, and whose script is either unspecified or matches, is
+/// used.  Charset names are those registered with IANA.
+static LEGACY_CHARSETS: &[(&str, Option<&str>, &str)] = &[
+    ("ja", None, "Shift_JIS"),
+    ("ko", None, "EUC-KR"),
+    ("zh", Some("Hans"), "GBK"),
+    ("zh", Some("Hant"), "Big5"),
+    ("th", None, "windows-874"),
+    ("vi", None, "windows-1258"),
+    ("he", None, "windows-1255"),
+    ("yi", None, "windows-1255"),
+    ("ar", None, "windows-1256"),
+    ("fa", None, "windows-1256"),
+    ("ur", None, "windows-1256"),
+    ("el", None, "windows-1253"),
+    ("tr", None, "windows-1254"),
+    ("az", Some("Latn"), "windows-1254"),
+    ("et", None, "windows-1257"),
+    ("lt", None, "windows-1257"),
+    ("lv", None, "windows-1257"),
+    ("be", None, "windows-1251"),
+    ("bg", None, "windows-1251"),
+    ("kk", None, "windows-1251"),
+    ("mk", None, "windows-1251"),
+    ("ru", None, "windows-1251"),
+    ("uk", None, "windows-1251"),
+    ("az", Some("Cyrl"), "windows-1251"),
+    ("bs", Some("Cyrl"), "windows-1251"),
+    ("sr", Some("Cyrl"), "windows-1251"),
+    ("bs", Some("Latn"), "windows-1250"),
+    ("sr", Some("Latn"), "windows-1250"),
+    ("cs", None, "windows-1250"),
+    ("hr", None, "windows-1250"),
+    ("hu", None, "windows-1250"),
+    ("pl", None, "windows-1250"),
+    ("ro", None, "windows-1250"),
+    ("sk", None, "windows-1250"),
+    ("sl", None, "windows-1250"),
+    ("sq", None, "windows-1250"),
+    ("af", None, "windows-1252"),
+    ("ca", None, "windows-1252"),
+    ("da", None, "windows-1252"),
+    ("de", None, "windows-1252"),
+    ("en", None, "windows-1252"),
+    ("es", None, "windows-1252"),
+    ("eu", None, "windows-1252"),
+    ("fi", None, "windows-1252"),
+    ("fo", None, "windows-1252"),
+    ("fr", None, "windows-1252"),
+    ("ga", None, "windows-1252"),
+    ("gl", None, "windows-1252"),
+    ("id", None, "windows-1252"),
+    ("is", None, "windows-1252"),
+    ("it", None, "windows-1252"),
+    ("ms", None, "windows-1252"),
+    ("nb", None, "windows-1252"),
+    ("nl", None, "windows-1252"),
+    ("nn", None, "windows-1252"),
+    ("no", None, "windows-1252"),
+    ("pt", None, "windows-1252"),
+    ("sv", None, "windows-1252"),
+    ("sw", None, "windows-1252"),
+];
+
+/// OpenType language system tags by language and, where it decides the tag, script.
+///
+/// The first entry whose language matches, and whose script is either unspecified or matches, is
+/// used.  Tags are from the OpenType language system tag registry, without their padding.
+static OPENTYPE_LANGUAGE_TAGS: &[(&str, Option<&str>, &str)] = &[
+    ("ar", None, "ARA"),
+    ("bg", None, "BGR"),
+    ("bn", None, "BEN"),
+    ("ca", None, "CAT"),
+    ("cs", None, "CSY"),
+    ("cy", None, "WEL"),
+    ("da", None, "DAN"),
+    ("de", None, "DEU"),
+    ("el", None, "ELL"),
+    ("en", None, "ENG"),
+    ("es", None, "ESP"),
+    ("et", None, "ETI"),
+    ("eu", None, "EUQ"),
+    ("fa", None, "FAR"),
+    ("fi", None, "FIN"),
+    ("fr", None, "FRA"),
+    ("ga", None, "IRI"),
+    ("gu", None, "GUJ"),
+    ("he", None, "IWR"),
+    ("hi", None, "HIN"),
+    ("hr", None, "HRV"),
+    ("hu", None, "HUN"),
+    ("hy", None, "HYE"),
+    ("id", None, "IND"),
+    ("is", None, "ISL"),
+    ("it", None, "ITA"),
+    ("ja", None, "JAN"),
+    ("ka", None, "KAT"),
+    ("kk", None, "KAZ"),
+    ("km", None, "KHM"),
+    ("kn", None, "KAN"),
+    ("ko", None, "KOR"),
+    ("lo", None, "LAO"),
+    ("lt", None, "LTH"),
+    ("lv", None, "LVI"),
+    ("mk", None, "MKD"),
+    ("ml", None, "MAL"),
+    ("mn", None, "MNG"),
+    ("mr", None, "MAR"),
+    ("ms", None, "MLY"),
+    ("my", None, "BRM"),
+    ("nb", None, "NOR"),
+    ("ne", None, "NEP"),
+    ("nl", None, "NLD"),
+    ("nn", None, "NYN"),
+    ("pa", None, "PAN"),
+    ("pl", None, "PLK"),
+    ("pt", None, "PTG"),
+    ("ro", None, "ROM"),
+    ("ru", None, "RUS"),
+    ("si", None, "SNH"),
+    ("sk", None, "SKY"),
+    ("sl", None, "SLV"),
+    ("sq", None, "SQI"),
+    ("sr", None, "SRB"),
+    ("sv", None, "SVE"),
+    ("sw", None, "SWK"),
+    ("ta", None, "TAM"),
+    ("te", None, "TEL"),
+    ("th", None, "THA"),
+    ("tr", None, "TRK"),
+    ("uk", None, "UKR"),
+    ("ur", None, "URD"),
+    ("uz", None, "UZB"),
+    ("vi", None, "VIT"),
+    ("zh", Some("Hans"), "ZHS"),
+    ("zh", Some("Hant"), "ZHT"),
+];
+
+/// ISO 15924 codes of the scripts whose text needs complex shaping, in ascending order.
+///
+/// These are the scripts with cursive joining, such as Arabic, and those whose characters are
+/// reordered or stacked into clusters, such as the Indic and Southeast Asian scripts; see the
+/// script-specific shaping guidance of the OpenType specification.
+static COMPLEX_SHAPING_SCRIPTS: &[&str] = &[
+    "Adlm", "Arab", "Bali", "Beng", "Deva", "Gujr", "Guru", "Java", "Khmr", "Knda", "Lana", "Laoo",
+    "Mand", "Mani", "Mlym", "Mong", "Mymr", "Nkoo", "Orya", "Phag", "Rohg", "Sinh", "Sogd", "Syrc",
+    "Taml", "Telu", "Thai", "Tibt",
+];
+
+/// Windows locale names that are not BCP-47 language tags, and the tags they stand for.
+///
+/// These are the legacy names of the neutral Chinese locales, which Windows still accepts.
+static WINDOWS_LOCALE_NAMES: &[(&str, &str)] = &[("zh-CHS", "zh-Hans"), ("zh-CHT", "zh-Hant")];
+
+/// Windows sort order suffixes, and the values of the ICU `collation` keyword they stand for.
+///
+/// A suffix follows the locale name after an underscore, as in `de-DE_phoneb`.  Windows sort
+/// orders with no ICU collation, such as `hu-HU_tchncl`, are left out.
+static WINDOWS_SORT_ORDERS: &[(&str, &str)] = &[
+    ("phoneb", "phonebook"),
+    ("pronun", "zhuyin"),
+    ("radstr", "unihan"),
+    ("stroke", "stroke"),
+    ("tradnl", "traditional"),
+];
+
+/// The kind of a region, as classified by ICU's region data.
+///
+/// Mirrors `URegionType` from ICU4C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionType {
+    /// A region that ICU does not classify.
+    Unknown,
+    /// A territory, such as `US`.
+    Territory,
+    /// The world, `001`.
+    World,
+    /// A continent, such as `002` for Africa.
+    Continent,
+    /// A subcontinent, such as `021` for Northern America.
+    Subcontinent,
+    /// A grouping of territories that is not geographic, such as `EU` for the European Union.
+    Grouping,
+    /// A region code that is no longer in use, such as `SU`.
+    Deprecated,
+}
+
+impl From<sys::URegionType> for RegionType {
+    fn from(region_type: sys::URegionType) -> Self {
+        match region_type {
+            sys::URegionType::URGN_TERRITORY => RegionType::Territory,
+            sys::URegionType::URGN_WORLD => RegionType::World,
+            sys::URegionType::URGN_CONTINENT => RegionType::Continent,
+            sys::URegionType::URGN_SUBCONTINENT => RegionType::Subcontinent,
+            sys::URegionType::URGN_GROUPING => RegionType::Grouping,
+            sys::URegionType::URGN_DEPRECATED => RegionType::Deprecated,
+            _ => RegionType::Unknown,
+        }
+    }
+}
+
+/// Whether plural rules apply to counts of things or to positions in a sequence.
+///
+/// Mirrors `UPluralType` from ICU4C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluralKind {
+    /// Plural rules for counts, as in "1 day", "2 days".
+    Cardinal,
+    /// Plural rules for ordinal numbers, as in "1st", "2nd".
+    Ordinal,
+}
+
+/// A kind of ICU service, whose data can be looked up for a locale.
+///
+/// See [ULoc::resolved_locale].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    /// Collation data, as used by `ucol`.
+    Collation,
+    /// Number formatting data, as used by `unum`.
+    NumberFormat,
+    /// Calendar data, as used by `ucal`.
+    Calendar,
+}
+
+/// An ICU service with its own list of available locales.
+///
+/// See [ULoc::is_available_for].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IcuService {
+    /// Collation, as used by `ucol`.
+    Collator,
+    /// Date formatting, as used by `udat`.
+    DateFormat,
+    /// Number formatting, as used by `unum`.
+    NumberFormat,
+    /// Text boundary analysis, as used by `ubrk`.
+    BreakIterator,
+}
+
+/// A kind of time zone name.
+///
+/// Mirrors `UTimeZoneNameType` from the ICU4C C++ API.  See [ULoc::time_zone_display_name].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeZoneNameStyle {
+    /// The name for all times of the year, such as "Pacific Time".
+    LongGeneric,
+    /// The name for standard time, such as "Pacific Standard Time".
+    LongStandard,
+    /// The name for daylight saving time, such as "Pacific Daylight Time".
+    LongDaylight,
+    /// The abbreviated name for all times of the year, such as "PT".
+    ShortGeneric,
+    /// The abbreviated name for standard time, such as "PST".
+    ShortStandard,
+    /// The abbreviated name for daylight saving time, such as "PDT".
+    ShortDaylight,
+    /// The name of the city that the zone is named after, such as "Los Angeles".
+    ExemplarLocation,
+}
+
+impl From<PluralKind> for sys::UPluralType {
+    fn from(kind: PluralKind) -> Self {
+        match kind {
+            PluralKind::Cardinal => sys::UPluralType::UPLURAL_TYPE_CARDINAL,
+            PluralKind::Ordinal => sys::UPluralType::UPLURAL_TYPE_ORDINAL,
+        }
+    }
+}
+
+/// The names of the keywords that differ between two locales.
+///
+/// See [ULoc::keyword_diff].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeywordDiff {
+    /// Keywords that only the second locale has.
+    pub added: BTreeSet<String>,
+    /// Keywords that only the first locale has.
+    pub removed: BTreeSet<String>,
+    /// Keywords that both locales have, with different values.
+    pub changed: BTreeSet<String>,
+}
+
 /// A representation of a Unicode locale.
 ///
 /// For the time being, only basic conversion and methods are in fact implemented.
 ///
 /// To get basic validation when creating a locale, use
 /// [`for_language_tag`](ULoc::for_language_tag) with a Unicode BCP-47 locale ID.
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Clone, Eq, PartialEq, Hash)]
 pub struct ULoc {
     // A locale's representation in C is really just a string.
     repr: String,
 }
 
+/// A value that can be infallibly converted into a [ULoc].
+///
+/// This is implemented for `ULoc` and `&ULoc`, so that functions like [accept_language] can take
+/// either.  String tags must be validated before they become locales; use [locales_from_tags] to
+/// convert a collection of them up front.
+pub trait IntoLocale {
+    /// Converts `self` into a [ULoc].
+    fn into_locale(self) -> ULoc;
+}
+
+impl IntoLocale for ULoc {
+    fn into_locale(self) -> ULoc {
+        self
+    }
+}
+
+impl IntoLocale for &ULoc {
+    fn into_locale(self) -> ULoc {
+        self.clone()
+    }
+}
+
+/// Converts a collection of locale IDs into locales, failing on the first one that is invalid.
+pub fn locales_from_tags<'a>(
+    tags: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<ULoc>, common::Error> {
+    tags.into_iter().map(ULoc::try_from).collect()
+}
+
 /// Implement the Display trait to convert the ULoc into string for display.
 ///
 /// The string for display and string serialization happen to be the same for [ULoc].
@@ -52,14 +414,83 @@ impl fmt::Display for ULoc {
     }
 }
 
+/// Shows the locale ID; the alternate form, `{:#?}`, also shows the locale's parts.
+///
+/// If the parts can not be extracted, the alternate form falls back to showing only the ID.
+impl fmt::Debug for ULoc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let alternate = f.alternate();
+        let mut debug = f.debug_struct("ULoc");
+        debug.field("repr", &self.repr);
+        if alternate {
+            if let (Ok(parts), Ok(keywords)) = (self.debug_parts(), self.debug_keywords()) {
+                for (name, value) in parts {
+                    debug.field(name, &value);
+                }
+                debug.field("keywords", &keywords);
+            }
+        }
+        debug.finish()
+    }
+}
+
 impl TryFrom<&str> for ULoc {
     type Error = common::Error;
     /// Creates a new ULoc from a string slice.
     ///
-    /// The creation wil fail if the locale is nonexistent.
+    /// The creation wil fail if the locale is nonexistent.  The error message then includes `s`,
+    /// while [common::Error::error_code] still reports the underlying ICU error code.
+    ///
+    /// Leading and trailing ASCII whitespace, and a leading byte order mark, are removed from `s`
+    /// first, as they are often left around values read from configuration files.
+    ///
+    /// With the `canonicalize_cache` feature, the canonical forms of recently used strings are
+    /// cached, so that converting the same string again does not call into ICU.
     fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let s = String::from(s);
-        ULoc { repr: s }.canonicalize()
+        ULoc::try_from(Cow::Borrowed(s))
+    }
+}
+
+impl<'a> TryFrom<Cow<'a, str>> for ULoc {
+    type Error = common::Error;
+
+    /// Creates a new ULoc from a borrowed or owned string, as [ULoc::try_from] does for a
+    /// string slice.
+    ///
+    /// The buffer of an owned string is reused for the C string that is handed to ICU, so the
+    /// string is not copied first.
+    fn try_from(s: Cow<'a, str>) -> Result<Self, Self::Error> {
+        let s = match s {
+            Cow::Borrowed(s) => Cow::Borrowed(trim_locale_id(s)),
+            Cow::Owned(s) if trim_locale_id(&s).len() == s.len() => Cow::Owned(s),
+            Cow::Owned(s) => Cow::Owned(trim_locale_id(&s).to_string()),
+        };
+        #[cfg(feature = "canonicalize_cache")]
+        {
+            if let Some(repr) = canonicalize_cache::get(&s) {
+                return Ok(ULoc { repr });
+            }
+        }
+        let invalid = |id: &str, e: common::Error| {
+            common::Error::wrapper(
+                anyhow::Error::new(e).context(format!("invalid locale {:?}", id)),
+            )
+        };
+        let id = ffi::CString::new(s.into_owned().into_bytes())?;
+        let id_str = id.to_str()?;
+        let loc = ULoc::canonicalize_c_str(&id).map_err(|e| invalid(id_str, e))?;
+        #[cfg(feature = "canonicalize_cache")]
+        canonicalize_cache::insert(id_str, &loc.repr);
+        Ok(loc)
+    }
+}
+
+impl std::str::FromStr for ULoc {
+    type Err = common::Error;
+
+    /// Same as [ULoc::try_from], so that locales can be parsed with [str::parse].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ULoc::try_from(s)
     }
 }
 
@@ -76,6 +507,29 @@ impl TryFrom<&ffi::CStr> for ULoc {
     }
 }
 
+#[cfg(feature = "icu_locid-interop")]
+impl TryFrom<&ULoc> for icu_locid::Locale {
+    type Error = common::Error;
+
+    /// Converts a `ULoc` to an `icu_locid::Locale` through its strict BCP-47 language tag.
+    fn try_from(loc: &ULoc) -> Result<Self, Self::Error> {
+        let tag = loc.to_language_tag(true)?;
+        tag.parse().map_err(|e| {
+            common::Error::wrapper(anyhow!("can not convert {:?} to icu_locid: {}", tag, e))
+        })
+    }
+}
+
+#[cfg(feature = "icu_locid-interop")]
+impl TryFrom<&icu_locid::Locale> for ULoc {
+    type Error = common::Error;
+
+    /// Converts an `icu_locid::Locale` to a `ULoc` through its BCP-47 language tag.
+    fn try_from(locale: &icu_locid::Locale) -> Result<Self, Self::Error> {
+        ULoc::for_language_tag(&locale.to_string())
+    }
+}
+
 impl ULoc {
     /// Implements `uloc_getLanguage`.
     pub fn language(&self) -> Option<String> {
@@ -87,20 +541,212 @@ impl ULoc {
         self.call_buffered_string_method_to_option(versioned_function!(uloc_getScript))
     }
 
+    /// Like [script](ULoc::script), but returns an error if this locale has no script subtag.
+    ///
+    /// For pipelines where the script may be implied rather than explicit, call
+    /// [add_likely_subtags](ULoc::add_likely_subtags) first: `en` has no script, but its likely
+    /// form `en_Latn_US` does.
+    pub fn require_script(&self) -> Result<String, common::Error> {
+        self.script().ok_or_else(|| {
+            common::Error::wrapper(anyhow!("locale {:?} has no script subtag", self.repr))
+        })
+    }
+
+    /// Returns the script this locale is most likely written in, such as `Latn` for `en`, or
+    /// `Cyrl` for `ru`.
+    ///
+    /// This is the script of the locale maximized with
+    /// [add_likely_subtags](ULoc::add_likely_subtags), so an explicit script is returned as it
+    /// is.  Returns `None` if ICU can not infer a script.
+    pub fn likely_script(&self) -> Result<Option<String>, common::Error> {
+        Ok(self.add_likely_subtags()?.script())
+    }
+
+    /// Returns a short sample of text in the [likely_script](ULoc::likely_script) of this
+    /// locale, such as `か` for `ja` or `ب` for `ar`, for example to preview a font.
+    ///
+    /// Implements `uscript_getSampleString` from ICU4C.  Returns `None` if ICU can not infer a
+    /// script, or has no sample for it.
+    pub fn script_sample(&self) -> Result<Option<String>, common::Error> {
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_sample_string,
+            8,
+            [script: sys::UScriptCode,],
+            []
+        );
+        let script = match self.likely_script()? {
+            Some(script) => script,
+            None => return Ok(None),
+        };
+        let name = str_to_cstring(&script);
+        let mut code = sys::UScriptCode::USCRIPT_INVALID_CODE;
+        let mut status = common::Error::OK_CODE;
+        let count = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(uscript_getCode)(name.as_ptr(), &mut code, 1, &mut status)
+        };
+        common::Error::ok_or_warning(status)?;
+        if count == 0 {
+            return Ok(None);
+        }
+        let sample =
+            buffered_uchar_sample_string(versioned_function!(uscript_getSampleString), code)?;
+        let sample = String::try_from(&sample)?;
+        Ok(if sample.is_empty() {
+            None
+        } else {
+            Some(sample)
+        })
+    }
+
     /// Implements `uloc_getCountry`.
     pub fn country(&self) -> Option<String> {
         self.call_buffered_string_method_to_option(versioned_function!(uloc_getCountry))
     }
 
+    /// Returns the available locales with the same language subtag as this one, ordered by
+    /// region, such as `en`, `en_001`, ..., `en_GB`, ..., `en_US`, ... for `en`.
+    ///
+    /// Locales without a region come first; locales with the same region are ordered by their
+    /// labels.  The result includes this locale if it is available.
+    pub fn sibling_locales(&self) -> Vec<ULoc> {
+        let language = match self.language() {
+            Some(language) => language,
+            None => return vec![],
+        };
+        let mut siblings: Vec<(String, ULoc)> = available_locales_with_language(&language)
+            .map(|uloc| (uloc.country().unwrap_or_default(), uloc))
+            .collect();
+        siblings.sort_by(|(a_region, a), (b_region, b)| {
+            a_region.cmp(b_region).then_with(|| a.repr.cmp(&b.repr))
+        });
+        siblings.into_iter().map(|(_, uloc)| uloc).collect()
+    }
+
+    /// Returns the region this locale most likely refers to, such as `US` for `en`, or `BR` for
+    /// `pt`.
+    ///
+    /// This is the region of the locale maximized with
+    /// [add_likely_subtags](ULoc::add_likely_subtags), so an explicit region is returned as it
+    /// is.  Returns `None` if ICU can not infer a region.
+    pub fn likely_region(&self) -> Result<Option<String>, common::Error> {
+        Ok(self.add_likely_subtags()?.country())
+    }
+
+    /// Returns the region where the language of this locale is most likely written in its
+    /// script, such as `TW` for `zh_Hant`, or `CN` for `zh_Hans`.
+    ///
+    /// Unlike [likely_region](ULoc::likely_region), only the language and the script are
+    /// maximized, so any region of this locale is ignored: `zh_Hant_HK` also yields `TW`.
+    /// Returns `None` if this locale has no script subtag, or if ICU can not infer a region.
+    pub fn region_for_script(&self) -> Result<Option<String>, common::Error> {
+        let script = match self.script() {
+            Some(script) => script,
+            None => return Ok(None),
+        };
+        let language = self.language().unwrap_or_else(|| "und".to_string());
+        ULoc::try_from(format!("{}_{}", language, script).as_str())?.likely_region()
+    }
+
     /// Implements `uloc_getVariant`.
     pub fn variant(&self) -> Option<String> {
         self.call_buffered_string_method_to_option(versioned_function!(uloc_getVariant))
     }
 
+    /// Returns a copy of this locale with its variant replaced by `variant`, such as `PINYIN`.
+    ///
+    /// The language, script, country and keywords are preserved.
+    pub fn with_variant(&self, variant: &str) -> Result<ULoc, common::Error> {
+        self.with_replaced_variant(Some(variant))
+    }
+
+    /// Returns a copy of this locale without its variant.
+    ///
+    /// The language, script, country and keywords are preserved.
+    pub fn clear_variant(&self) -> Result<ULoc, common::Error> {
+        self.with_replaced_variant(None)
+    }
+
+    /// Implements `uregion_getType` from ICU4C, for the region of this locale.
+    ///
+    /// Returns an error if this locale has no region, or one that ICU does not know.
+    pub fn region_type(&self) -> Result<RegionType, common::Error> {
+        let region = self.uregion()?;
+        // Unsafety note: uregion() returns a valid pointer to ICU's static region data.
+        let region_type = unsafe { versioned_function!(uregion_getType)(region) };
+        Ok(region_type.into())
+    }
+
+    /// Implements `uregion_getContainingRegion` from ICU4C, for the region of this locale.
+    ///
+    /// Returns the code of the region immediately containing this locale's region, such as `021`
+    /// (Northern America) for `en_US`.  Returns `None` if this locale has no region, or if its
+    /// region is the world, `001`, which nothing contains.
+    pub fn containing_region(&self) -> Result<Option<String>, common::Error> {
+        if self.country().is_none() {
+            return Ok(None);
+        }
+        let region = self.uregion()?;
+        // Unsafety note: uregion() returns a valid pointer to ICU's static region data, and so
+        // does uregion_getContainingRegion, unless it returns null.
+        let code = unsafe {
+            let containing = versioned_function!(uregion_getContainingRegion)(region);
+            if containing.is_null() {
+                return Ok(None);
+            }
+            ffi::CStr::from_ptr(versioned_function!(uregion_getRegionCode)(containing))
+        };
+        Ok(Some(code.to_str()?.to_string()))
+    }
+
     /// Implements `uloc_canonicalize` from ICU4C.
     pub fn canonicalize(&self) -> Result<ULoc, common::Error> {
-        self.call_buffered_string_method(versioned_function!(uloc_canonicalize))
-            .map(|repr| ULoc { repr })
+        ULoc::canonicalize_c_str(&self.as_c_str())
+    }
+
+    /// Implements `uloc_canonicalize` from ICU4C, for a locale ID that is already a C string.
+    fn canonicalize_c_str(id: &ffi::CStr) -> Result<ULoc, common::Error> {
+        buffered_string_method_with_retry!(
+            buffered_string_canonicalize,
+            LOCALE_CAPACITY,
+            [locale_id: *const raw::c_char,],
+            []
+        );
+        let loc = ULoc {
+            repr: buffered_string_canonicalize(
+                versioned_function!(uloc_canonicalize),
+                id.as_ptr(),
+            )?,
+        };
+        debug_assert!(
+            !id.to_bytes().is_ascii() || loc.is_ascii_id(),
+            "non-ASCII locale ID {:?} canonicalized from {:?}",
+            loc.repr,
+            id
+        );
+        Ok(loc)
+    }
+
+    /// Returns whether this locale uses deprecated codes for its language or region, such as
+    /// `iw` for Hebrew, `sh` for Serbo-Croatian, or `no_NO_NY` for Norwegian Nynorsk.
+    ///
+    /// A locale is deprecated if converting it to a language tag replaces its language or its
+    /// region with the modern code, as for `iw` or `de_DD`.  A few deprecated IDs that ICU keeps
+    /// as they are, such as `sh`, are recognized from a table of CLDR aliases.
+    pub fn is_deprecated(&self) -> Result<bool, common::Error> {
+        let language = self.language();
+        let variant = self.variant();
+        if DEPRECATED_IDS
+            .iter()
+            .any(|(deprecated_language, deprecated_variant)| {
+                language.as_deref() == Some(*deprecated_language)
+                    && (deprecated_variant.is_none() || *deprecated_variant == variant.as_deref())
+            })
+        {
+            return Ok(true);
+        }
+        let modern = ULoc::for_language_tag(&self.to_language_tag(false)?)?;
+        Ok(modern.language() != language || modern.country() != self.country())
     }
 
     /// Implements `uloc_addLikelySubtags` from ICU4C.
@@ -109,12 +755,186 @@ impl ULoc {
             .map(|repr| ULoc { repr })
     }
 
+    /// Like [add_likely_subtags](ULoc::add_likely_subtags), but also tells whether the result
+    /// comes from an entry for exactly this locale's subtags in the CLDR likely subtags data.
+    ///
+    /// ICU does not report which entry it used, so this follows the CLDR algorithm instead: the
+    /// result is derived, rather than exact, if maximizing this locale with its script or region
+    /// left out, and then putting them back, gives the same result.  For example `zh_TW` has an
+    /// entry of its own, as its likely script `Hant` differs from the `Hans` of `zh`, while `en_CH`
+    /// is `en` maximized to `en_Latn_US` with the region replaced.  Locales for which there is no
+    /// data at all are not exact either.  Only the language, script and region are considered.
+    pub fn likely_subtags_detailed(&self) -> Result<(ULoc, bool), common::Error> {
+        let maximized = self.add_likely_subtags()?;
+        let language = self.language().unwrap_or_else(|| "und".to_string());
+        let script = self.script();
+        let region = self.country();
+        let base_name = |script: Option<&str>, region: Option<&str>| {
+            let mut base_name = language.clone();
+            if let Some(script) = script {
+                base_name.push('_');
+                base_name.push_str(script);
+            }
+            if let Some(region) = region {
+                base_name.push('_');
+                base_name.push_str(region);
+            }
+            ULoc::try_from(base_name.as_str())
+        };
+        let base = base_name(script.as_deref(), region.as_deref())?;
+        let own_base = base.add_likely_subtags()?;
+        if own_base == base {
+            // Nothing was added, so no data was used.
+            return Ok((maximized, false));
+        }
+        let mut fewer = vec![];
+        if script.is_some() || region.is_some() {
+            fewer.push((None, None));
+        }
+        if script.is_some() && region.is_some() {
+            fewer.push((script.as_deref(), None));
+            fewer.push((None, region.as_deref()));
+        }
+        for (fewer_script, fewer_region) in fewer {
+            let derived = base_name(fewer_script, fewer_region)?.add_likely_subtags()?;
+            let derived = base_name(
+                script.as_deref().or(derived.script().as_deref()),
+                region.as_deref().or(derived.country().as_deref()),
+            )?;
+            if derived == own_base {
+                return Ok((maximized, false));
+            }
+        }
+        Ok((maximized, true))
+    }
+
     /// Implements `uloc_minimizeSubtags` from ICU4C.
     pub fn minimize_subtags(&self) -> Result<ULoc, common::Error> {
         self.call_buffered_string_method(versioned_function!(uloc_minimizeSubtags))
             .map(|repr| ULoc { repr })
     }
 
+    /// Returns the BCP-47 language tag of this locale with its likely subtags removed, such as
+    /// `sr-u-ca-gregory` for `sr_Cyrl_RS@calendar=gregory`.
+    ///
+    /// Keywords are kept and written as `-u-` extension subtags.  See
+    /// [minimize_subtags](ULoc::minimize_subtags) and [to_language_tag](ULoc::to_language_tag).
+    pub fn minimized_language_tag(&self) -> Result<String, common::Error> {
+        self.minimize_subtags()?.to_language_tag(false)
+    }
+
+    /// Implements `uloc_getParent` from ICU4C.
+    ///
+    /// The parent is found by removing the last subtag from this locale, so the parent of
+    /// `de_CH` is `de`, and the parent of `de` is the root locale.  Returns `None` for the root
+    /// locale, which has no parent.
+    pub fn parent(&self) -> Option<ULoc> {
+        if self.repr.is_empty() {
+            return None;
+        }
+        self.call_buffered_string_method(versioned_function!(uloc_getParent))
+            .map(|repr| ULoc { repr })
+            .ok()
+    }
+
+    /// Returns the parent of this locale in the CLDR parent locale data, which ICU's resource
+    /// fallback follows, or `None` for the root locale.
+    ///
+    /// This differs from [parent](ULoc::parent) where CLDR groups locales of a language, so
+    /// the parent of `en_GB` is `en_001`, and that of `es_MX` is `es_419`.  It also leads to the
+    /// root locale straight from some locales with a non-default script, such as `zh_Hant`.
+    /// Otherwise the last subtag is removed as with `parent`.  Keywords are dropped.
+    pub fn cldr_parent(&self) -> Result<Option<ULoc>, common::Error> {
+        let base = self.base_name()?;
+        if base.repr.is_empty() {
+            return Ok(None);
+        }
+        let asciiz_locale = base.as_c_str();
+        let mut status = common::Error::OK_CODE;
+        // Opens the data of exactly this locale, without falling back to its parents.
+        let bundle = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(ures_openDirect)(ptr::null(), asciiz_locale.as_ptr(), &mut status)
+        };
+        let parent = if status == UErrorCode::U_MISSING_RESOURCE_ERROR {
+            Ok(None)
+        } else {
+            common::Error::ok_or_warning(status).and_then(|_| {
+                let key = str_to_cstring("%%Parent");
+                let mut status = common::Error::OK_CODE;
+                let mut len: i32 = 0;
+                let value = unsafe {
+                    versioned_function!(ures_getStringByKey)(
+                        bundle,
+                        key.as_ptr(),
+                        &mut len,
+                        &mut status,
+                    )
+                };
+                if status == UErrorCode::U_MISSING_RESOURCE_ERROR {
+                    return Ok(None);
+                }
+                common::Error::ok_or_warning(status)?;
+                let value = unsafe { std::slice::from_raw_parts(value, len as usize) };
+                String::from_utf16(value)
+                    .map(Some)
+                    .map_err(|e| common::Error::wrapper(anyhow!(e)))
+            })
+        };
+        unsafe { versioned_function!(ures_close)(bundle) };
+        match parent? {
+            Some(parent) if parent == "root" => Ok(Some(ULoc {
+                repr: String::new(),
+            })),
+            Some(parent) => ULoc::try_from(parent.as_str()).map(Some),
+            None => Ok(base.parent()),
+        }
+    }
+
+    /// Returns this locale followed by its chain of parents, ending with the root locale.
+    ///
+    /// See [parent](ULoc::parent).
+    pub fn fallback_iter(&self) -> impl Iterator<Item = ULoc> {
+        std::iter::successors(Some(self.clone()), ULoc::parent)
+    }
+
+    /// Returns this locale and its parents as in [fallback_iter](ULoc::fallback_iter), up to and
+    /// including `stop`.
+    ///
+    /// For example, `sr_Latn_RS` with the stop `sr` yields `sr_Latn_RS`, `sr_Latn` and `sr`.  If
+    /// `stop` is not in the chain, the whole chain, ending with the root locale, is returned.
+    pub fn ancestors_until(&self, stop: &ULoc) -> Vec<ULoc> {
+        let mut chain = vec![];
+        for locale in self.fallback_iter() {
+            let done = &locale == stop;
+            chain.push(locale);
+            if done {
+                break;
+            }
+        }
+        chain
+    }
+
+    /// Returns the order in which a resource loader should look up resources for this locale,
+    /// when it is configured with a `default` locale.
+    ///
+    /// The chain is this locale and its parents as in [fallback_iter](ULoc::fallback_iter), then
+    /// `default` and its parents, skipping locales already in the chain, and finally the root
+    /// locale.  For example, `de_CH` with the default `en_US` yields `de_CH`, `de`, `en_US`,
+    /// `en`, and root.
+    pub fn fallback_chain_with_default(&self, default: &ULoc) -> Vec<ULoc> {
+        let mut chain: Vec<ULoc> = self
+            .fallback_iter()
+            .filter(|locale| !locale.repr.is_empty())
+            .collect();
+        for locale in default.fallback_iter() {
+            if !chain.contains(&locale) {
+                chain.push(locale);
+            }
+        }
+        chain
+    }
+
     /// Implements `uloc_toLanguageTag` from ICU4C.
     pub fn to_language_tag(&self, strict: bool) -> Result<String, common::Error> {
         buffered_string_method_with_retry!(
@@ -134,416 +954,4477 @@ impl ULoc {
         )
     }
 
-    /// Implements `uloc_openKeywords()` from ICU4C.
-    pub fn keywords(&self) -> impl Iterator<Item = String> {
-        rust_icu_uenum::uloc_open_keywords(&self.repr)
-            .unwrap()
-            .map(|result| result.unwrap())
+    /// Returns the strict BCP-47 language tag of this locale, such as `sr-Cyrl-RS` for
+    /// `sr_Cyrl_RS`, for display where an error can not be handled.
+    ///
+    /// If the locale has no strict language tag, because it has parts that BCP-47 can not
+    /// express, such as the one-letter variant in `en_US_X`, the ICU [label](ULoc::label) is
+    /// returned instead.  Use [to_language_tag](ULoc::to_language_tag) to tell the two apart.
+    pub fn language_tag_or_label(&self) -> String {
+        self.to_language_tag(true)
+            .unwrap_or_else(|_| self.label().to_string())
     }
 
-    /// Implements `icu::Locale::getUnicodeKeywords()` from the C++ API.
-    pub fn unicode_keywords(&self) -> impl Iterator<Item = String> {
-        self.keywords().filter_map(|s| to_unicode_locale_key(&s))
+    /// Like [to_language_tag](ULoc::to_language_tag), but writes the tag into `out`, replacing
+    /// its contents.
+    ///
+    /// The tag is written into the existing allocation of `out` whenever it fits, so a `String`
+    /// reused across calls stops allocating once it has grown to the longest tag written.  On
+    /// error, `out` is left empty.
+    pub fn to_language_tag_into(
+        &self,
+        strict: bool,
+        out: &mut String,
+    ) -> Result<(), common::Error> {
+        let locale_id = self.as_c_str();
+        // No `UBool` constants available in rust_icu_sys, unfortunately.
+        let strict = if strict { 1 } else { 0 };
+        let mut buf = std::mem::take(out).into_bytes();
+        buf.clear();
+        if buf.capacity() == 0 {
+            buf.reserve(LOCALE_CAPACITY);
+        }
+        loop {
+            buf.resize(buf.capacity(), 0);
+            let mut status = common::Error::OK_CODE;
+            let full_len: i32 = unsafe {
+                assert!(common::Error::is_ok(status));
+                versioned_function!(uloc_toLanguageTag)(
+                    locale_id.as_ptr(),
+                    buf.as_mut_ptr() as *mut raw::c_char,
+                    buf.len() as i32,
+                    strict,
+                    &mut status,
+                )
+            };
+            let full_len: usize = full_len.try_into().map_err(common::Error::wrapper)?;
+            if status == UErrorCode::U_BUFFER_OVERFLOW_ERROR || full_len > buf.len() {
+                buf.clear();
+                buf.reserve(full_len + 1);
+                continue;
+            }
+            common::Error::ok_or_warning(status)?;
+            buf.truncate(full_len);
+            *out = String::from_utf8(buf)?;
+            return Ok(());
+        }
     }
 
-    /// Implements `uloc_getKeywordValue()` from ICU4C.
-    pub fn keyword_value(&self, keyword: &str) -> Result<Option<String>, common::Error> {
-        buffered_string_method_with_retry!(
-            buffered_string_keyword_value,
-            LOCALE_CAPACITY,
-            [
-                locale_id: *const raw::c_char,
-                keyword_name: *const raw::c_char,
-            ],
-            []
-        );
-        let locale_id = self.as_c_str();
-        let keyword_name = str_to_cstring(keyword);
-        buffered_string_keyword_value(
-            versioned_function!(uloc_getKeywordValue),
-            locale_id.as_ptr(),
-            keyword_name.as_ptr(),
-        )
-        .map(|value| if value.is_empty() { None } else { Some(value) })
+    /// Like [to_language_tag](ULoc::to_language_tag), but returns the UTF-8 bytes of the tag.
+    ///
+    /// The bytes are moved out of the tag's string, so no copy is made.
+    pub fn to_language_tag_bytes(&self, strict: bool) -> Result<Vec<u8>, common::Error> {
+        self.to_language_tag(strict).map(String::into_bytes)
     }
 
-    /// Implements `icu::Locale::getUnicodeKeywordValue()` from ICU4C.
-    pub fn unicode_keyword_value(
-        &self,
-        unicode_keyword: &str,
-    ) -> Result<Option<String>, common::Error> {
-        let legacy_keyword = to_legacy_key(unicode_keyword);
-        match legacy_keyword {
-            Some(legacy_keyword) => match self.keyword_value(&legacy_keyword) {
-                Ok(Some(legacy_value)) => {
-                    Ok(to_unicode_locale_type(&legacy_keyword, &legacy_value))
+    /// Returns the BCP-47 language tag of the [base_name](ULoc::base_name) of this locale, such as
+    /// `th-TH` for `th-TH-u-ca-buddhist`.
+    pub fn base_language_tag(&self) -> Result<String, common::Error> {
+        self.base_name()?.to_language_tag(false)
+    }
+
+    /// Returns the Windows locale name of this locale, such as `en-US`, or `zh-CHS` for
+    /// `zh_Hans`.
+    ///
+    /// This is the reverse of [from_windows_locale_name](ULoc::from_windows_locale_name): the
+    /// name is the [base_language_tag](ULoc::base_language_tag), with the legacy names of the
+    /// neutral Chinese locales, and a sort order suffix for the `collation` keyword, such as
+    /// `de-DE_phoneb` for `de_DE@collation=phonebook`.  The conversion is lossy: collations with
+    /// no Windows sort order, and all other keywords, are dropped.
+    pub fn to_windows_locale_name(&self) -> Result<String, common::Error> {
+        let tag = self.base_language_tag()?;
+        let mut name = WINDOWS_LOCALE_NAMES
+            .iter()
+            .find(|(_, windows_tag)| windows_tag.eq_ignore_ascii_case(&tag))
+            .map_or(tag, |(windows_name, _)| windows_name.to_string());
+        if let Some(collation) = self.keyword_value("collation")? {
+            if let Some((sort_order, _)) = WINDOWS_SORT_ORDERS
+                .iter()
+                .find(|(_, windows_collation)| *windows_collation == collation)
+            {
+                name.push('_');
+                name.push_str(sort_order);
+            }
+        }
+        Ok(name)
+    }
+
+    /// Splits this locale into its base locale and its BCP-47 extensions.
+    ///
+    /// The extensions are keyed by their singleton, such as `'u'`, `'t'`, or `'x'` for private
+    /// use, and hold the hyphenated subtags following it, so `en-US-u-ca-gregory-x-private`
+    /// splits into `en_US` and the map `{'u': "ca-gregory", 'x': "private"}`.
+    pub fn split_extensions(&self) -> Result<(ULoc, BTreeMap<char, String>), common::Error> {
+        let tag = self.to_language_tag(false)?;
+        let mut base: Vec<&str> = vec![];
+        let mut extensions: BTreeMap<char, String> = BTreeMap::new();
+        let mut current: Option<char> = None;
+        for subtag in tag.split('-') {
+            let singleton = match subtag.chars().next() {
+                Some(c) if subtag.len() == 1 && current != Some('x') => Some(c),
+                _ => None,
+            };
+            match (singleton, current) {
+                (Some(singleton), _) => {
+                    current = Some(singleton);
+                    extensions.insert(singleton, String::new());
                 }
-                Ok(None) => Ok(None),
-                Err(e) => Err(e),
-            },
-            None => Ok(None),
+                (None, Some(singleton)) => {
+                    let content = extensions.get_mut(&singleton).unwrap();
+                    if !content.is_empty() {
+                        content.push('-');
+                    }
+                    content.push_str(subtag);
+                }
+                (None, None) => base.push(subtag),
+            }
         }
+        Ok((ULoc::for_language_tag(&base.join("-"))?, extensions))
     }
 
-    /// Returns the current label of this locale.
-    pub fn label(&self) -> &str {
-        &self.repr
+    /// Rebuilds a locale from a `base` locale and a map of BCP-47 extensions, as returned by
+    /// [split_extensions](ULoc::split_extensions).
+    ///
+    /// The extensions are appended in the order of their singletons, except that the private use
+    /// extension `'x'` always comes last, as BCP-47 requires.  Any extensions already on `base`
+    /// are kept in front of the new ones.
+    pub fn from_base_and_extensions(
+        base: &ULoc,
+        extensions: &BTreeMap<char, String>,
+    ) -> Result<ULoc, common::Error> {
+        let mut tag = base.to_language_tag(false)?;
+        let private_use = extensions.get(&'x');
+        let others = extensions
+            .iter()
+            .filter(|(singleton, _)| **singleton != 'x');
+        for (singleton, content) in others.chain(private_use.map(|content| (&'x', content))) {
+            tag.push('-');
+            tag.push(*singleton);
+            tag.push('-');
+            tag.push_str(content);
+        }
+        ULoc::for_language_tag(&tag)
     }
 
-    /// Returns the current locale name as a C string.
-    pub fn as_c_str(&self) -> ffi::CString {
-        ffi::CString::new(self.repr.clone()).expect("ULoc contained interior NUL bytes")
+    /// Returns the traditional, pre-Unicode charset used for text in this locale, such as
+    /// `Shift_JIS` for `ja_JP`, or `windows-1251` for `ru_RU`.
+    ///
+    /// This is a heuristic for decoding legacy files that are labeled only by their locale: it
+    /// looks up the language, and the likely script where that matters, in a fixed table of the
+    /// charsets that Windows and common legacy systems used.  Returns `None` for locales whose
+    /// languages had no widespread legacy charset.
+    pub fn legacy_charset(&self) -> Result<Option<String>, common::Error> {
+        let maximized = self.add_likely_subtags()?;
+        let language = maximized.language();
+        let script = maximized.script();
+        Ok(LEGACY_CHARSETS
+            .iter()
+            .find(|(charset_language, charset_script, _)| {
+                language.as_deref() == Some(*charset_language)
+                    && (charset_script.is_none() || *charset_script == script.as_deref())
+            })
+            .map(|(_, _, charset)| charset.to_string()))
     }
 
-    /// Implements `uloc_forLanguageTag` from ICU4C.
-    pub fn for_language_tag(tag: &str) -> Result<ULoc, common::Error> {
-        buffered_string_method_with_retry!(
-            buffered_string_for_language_tag,
-            LOCALE_CAPACITY,
-            [tag: *const raw::c_char,],
-            [parsed_length: *mut i32,]
-        );
+    /// Returns the OpenType language system tag for the language of this locale, such as `DEU`
+    /// for `de`, or `ZHT` for `zh_TW`.
+    ///
+    /// The registered tags are four characters long, padded with spaces; the padding is not
+    /// included here.  Where the script decides the tag, the likely script of the locale is used.
+    /// Returns `None` for languages not in the table of OpenType tags.
+    pub fn opentype_language_tag(&self) -> Option<String> {
+        let maximized = self.add_likely_subtags().unwrap_or_else(|_| self.clone());
+        let language = maximized.language();
+        let script = maximized.script();
+        OPENTYPE_LANGUAGE_TAGS
+            .iter()
+            .find(|(tag_language, tag_script, _)| {
+                language.as_deref() == Some(*tag_language)
+                    && (tag_script.is_none() || *tag_script == script.as_deref())
+            })
+            .map(|(_, _, tag)| tag.to_string())
+    }
 
-        let tag = str_to_cstring(tag);
-        let locale_id = buffered_string_for_language_tag(
-            versioned_function!(uloc_forLanguageTag),
-            tag.as_ptr(),
-            std::ptr::null_mut(),
-        )?;
-        ULoc::try_from(&locale_id[..])
+    /// Returns true if text in the [likely_script](ULoc::likely_script) of this locale needs
+    /// complex shaping to be rendered, as for `ar` and `hi`, but not for `en` or `ru`.
+    ///
+    /// Returns false if ICU can not infer a script.
+    pub fn needs_complex_shaping(&self) -> Result<bool, common::Error> {
+        Ok(match self.likely_script()? {
+            Some(script) => COMPLEX_SHAPING_SCRIPTS
+                .binary_search(&script.as_str())
+                .is_ok(),
+            None => false,
+        })
     }
 
-    /// Call a `uloc` method that takes this locale's ID and returns a string.
-    fn call_buffered_string_method(
-        &self,
-        uloc_method: unsafe extern "C" fn(
-            *const raw::c_char,
-            *mut raw::c_char,
-            i32,
-            *mut UErrorCode,
-        ) -> i32,
-    ) -> Result<String, common::Error> {
-        buffered_string_method_with_retry!(
-            buffered_string_char_star,
+    /// Returns the name of the numbering system used for numbers in this locale, such as `latn`
+    /// or `arab`.
+    ///
+    /// This is the value of the `numbers` keyword if the locale has one, and otherwise the default
+    /// numbering system of the locale.
+    ///
+    /// Implements `unumsys_open` and `unumsys_getName` from ICU4C.
+    pub fn numbering_system(&self) -> Result<String, common::Error> {
+        if let Some(numbers) = self.keyword_value("numbers")? {
+            return Ok(numbers);
+        }
+        let asciiz_locale = self.as_c_str();
+        let mut status = common::Error::OK_CODE;
+        let rep = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(unumsys_open)(asciiz_locale.as_ptr(), &mut status)
+        };
+        common::Error::ok_or_warning(status)?;
+        let name = unsafe {
+            let name = versioned_function!(unumsys_getName)(rep);
+            let name = ffi::CStr::from_ptr(name).to_owned();
+            versioned_function!(unumsys_close)(rep);
+            name
+        };
+        Ok(cstring_to_string(&name))
+    }
+
+    /// Returns the symbol that separates the integer part of a number from its fraction in this
+    /// locale, such as `.` for `en_US` or `,` for `de_DE`.
+    pub fn decimal_separator(&self) -> Result<String, common::Error> {
+        self.number_symbol(sys::UNumberFormatSymbol::UNUM_DECIMAL_SEPARATOR_SYMBOL)
+    }
+
+    /// Returns the symbol that separates groups of digits in numbers in this locale, such as `,`
+    /// for `en_US` or `.` for `de_DE`.
+    pub fn grouping_separator(&self) -> Result<String, common::Error> {
+        self.number_symbol(sys::UNumberFormatSymbol::UNUM_GROUPING_SEPARATOR_SYMBOL)
+    }
+
+    /// Returns `symbol` from the decimal format symbols of this locale.
+    ///
+    /// Implements `unum_open`, `unum_getSymbol` and `unum_close` from ICU4C.
+    fn number_symbol(&self, symbol: sys::UNumberFormatSymbol) -> Result<String, common::Error> {
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_number_symbol,
             LOCALE_CAPACITY,
-            [char_star: *const raw::c_char,],
+            [
+                fmt: *const sys::UNumberFormat,
+                symbol: sys::UNumberFormatSymbol,
+            ],
             []
         );
-        let asciiz = self.as_c_str();
-        buffered_string_char_star(uloc_method, asciiz.as_ptr())
+        let asciiz_locale = self.as_c_str();
+        let mut status = common::Error::OK_CODE;
+        let fmt = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(unum_open)(
+                sys::UNumberFormatStyle::UNUM_DECIMAL,
+                ptr::null(),
+                0,
+                asciiz_locale.as_ptr(),
+                ptr::null_mut(),
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        let uchar = buffered_uchar_number_symbol(
+            versioned_function!(unum_getSymbol),
+            fmt as *const sys::UNumberFormat,
+            symbol,
+        );
+        unsafe { versioned_function!(unum_close)(fmt) };
+        String::try_from(&uchar?)
     }
 
-    /// Call a `uloc` method that takes this locale's ID, panics on any errors, and returns
-    /// `Some(result)` if the resulting string is non-empty, or `None` otherwise.
-    fn call_buffered_string_method_to_option(
+    /// Spells out `n` in words in the language of this locale, such as "one hundred
+    /// twenty-three" for 123 in `en`, or "cent vingt-trois" in `fr`.
+    ///
+    /// Implements `unum_open` with `UNUM_SPELLOUT`, and `unum_formatDouble` from ICU4C.
+    pub fn spellout(&self, n: f64) -> Result<String, common::Error> {
+        self.format_number(
+            sys::UNumberFormatStyle::UNUM_SPELLOUT,
+            |fmt, dest, status| unsafe {
+                versioned_function!(unum_formatDouble)(
+                    fmt,
+                    n,
+                    dest.as_mut_ptr(),
+                    dest.len() as i32,
+                    ptr::null_mut(),
+                    status,
+                )
+            },
+        )
+    }
+
+    /// Formats `n` as an ordinal number in this locale, such as "1st", "2nd" or "3rd" in `en`,
+    /// or "1er" in `fr`.
+    ///
+    /// Implements `unum_open` with `UNUM_ORDINAL`, and `unum_formatInt64` from ICU4C.
+    pub fn format_ordinal(&self, n: i64) -> Result<String, common::Error> {
+        self.format_number(
+            sys::UNumberFormatStyle::UNUM_ORDINAL,
+            |fmt, dest, status| unsafe {
+                versioned_function!(unum_formatInt64)(
+                    fmt,
+                    n,
+                    dest.as_mut_ptr(),
+                    dest.len() as i32,
+                    ptr::null_mut(),
+                    status,
+                )
+            },
+        )
+    }
+
+    /// Returns the symbol of a currency in this locale, such as `$` for `USD` in `en_US`, or `€`
+    /// for `EUR` in `de_DE`.
+    ///
+    /// `currency_code` is an ISO 4217 code; if it is `None`, the currency of the region of this
+    /// locale is used.  If ICU has no symbol for the currency, the code itself is returned.
+    ///
+    /// Implements `ucurr_getName` with `UCURR_SYMBOL_NAME` from ICU4C.
+    pub fn currency_symbol(&self, currency_code: Option<&str>) -> Result<String, common::Error> {
+        let currency_code = match currency_code {
+            Some(currency_code) => currency_code.to_string(),
+            None => self.currency_code()?,
+        };
+        self.currency_name(&currency_code, |currency, locale, len, status| unsafe {
+            versioned_function!(ucurr_getName)(
+                currency,
+                locale,
+                sys::UCurrNameStyle::UCURR_SYMBOL_NAME,
+                ptr::null_mut(),
+                len,
+                status,
+            )
+        })
+    }
+
+    /// Returns the name of a currency in the language of this locale, such as "US Dollar" for
+    /// `USD` in `en`, or "US dollars" if `plural` is true.
+    ///
+    /// The plural name is the one for the `other` plural category, which is used for most
+    /// amounts.  If ICU has no name for the currency, the code itself is returned.
+    ///
+    /// Implements `ucurr_getName` with `UCURR_LONG_NAME`, and `ucurr_getPluralName` from ICU4C.
+    pub fn currency_display_name(
         &self,
-        uloc_method: unsafe extern "C" fn(
-            *const raw::c_char,
-            *mut raw::c_char,
-            i32,
-            *mut UErrorCode,
-        ) -> i32,
-    ) -> Option<String> {
-        let value: String = self.call_buffered_string_method(uloc_method).unwrap();
-        if value.is_empty() {
-            None
-        } else {
-            Some(value)
+        currency_code: &str,
+        plural: bool,
+    ) -> Result<String, common::Error> {
+        if !plural {
+            return self.currency_name(currency_code, |currency, locale, len, status| unsafe {
+                versioned_function!(ucurr_getName)(
+                    currency,
+                    locale,
+                    sys::UCurrNameStyle::UCURR_LONG_NAME,
+                    ptr::null_mut(),
+                    len,
+                    status,
+                )
+            });
         }
+        let plural_count = str_to_cstring("other");
+        self.currency_name(currency_code, |currency, locale, len, status| unsafe {
+            versioned_function!(ucurr_getPluralName)(
+                currency,
+                locale,
+                ptr::null_mut(),
+                plural_count.as_ptr(),
+                len,
+                status,
+            )
+        })
     }
-}
 
-/// This implementation is based on ULocale.compareTo from ICU4J.
-/// See https://github.com/unicode-org/icu/blob/master/icu4j/main/classes/core/src/com/ibm/icu/util/ULocale.java
-impl Ord for ULoc {
-    fn cmp(&self, other: &Self) -> Ordering {
-        /// Compare corresponding keywords from two `ULoc`s. If the keywords match, compare the
-        /// keyword values.
-        fn compare_keywords(
-            this: &ULoc,
-            self_keyword: &Option<String>,
-            other: &ULoc,
-            other_keyword: &Option<String>,
-        ) -> Option<Ordering> {
-            match (self_keyword, other_keyword) {
-                (Some(self_keyword), Some(other_keyword)) => {
-                    // Compare the two keywords
-                    match self_keyword.cmp(&other_keyword) {
-                        Ordering::Equal => {
-                            // Compare the two keyword values
-                            let self_val = this.keyword_value(&self_keyword[..]).unwrap();
-                            let other_val = other.keyword_value(&other_keyword[..]).unwrap();
-                            Some(self_val.cmp(&other_val))
-                        }
-                        unequal_ordering => Some(unequal_ordering),
-                    }
-                }
-                // `other` has run out of keywords
-                (Some(_), _) => Some(Ordering::Greater),
-                // `this` has run out of keywords
-                (_, Some(_)) => Some(Ordering::Less),
-                // Both iterators have run out
-                (_, _) => None,
+    /// Returns the name of the measurement unit `unit` in this locale, such as "kilometers per
+    /// hour" for `kilometer-per-hour` in `en`, or "km/h" in the short width.
+    ///
+    /// `unit` is a CLDR unit identifier, without its category.  These are supported:
+    ///
+    /// * the simple units of CLDR, such as `meter`, `kilometer`, `mile`, `foot`, `inch`, `gram`,
+    ///   `kilogram`, `pound`, `liter`, `gallon`, `second`, `minute`, `hour`, `day`, `celsius`,
+    ///   `fahrenheit`, `kilowatt-hour` or `megabyte`;
+    /// * the compound units that CLDR names, such as `kilometer-per-hour`, `mile-per-gallon`,
+    ///   `square-meter` or `cubic-foot`;
+    /// * other compounds of simple units with `-per-`, `square-` and `cubic-`, such as
+    ///   `foot-per-minute`, whose name recent versions of ICU put together from the names of
+    ///   their parts.
+    ///
+    /// `width` is one of `UNUM_UNIT_WIDTH_NARROW`, as in "′" for `foot` in `en`,
+    /// `UNUM_UNIT_WIDTH_SHORT`, as in "ft", and `UNUM_UNIT_WIDTH_FULL_NAME`, as in "feet"; the
+    /// other widths fail with `U_ILLEGAL_ARGUMENT_ERROR`, since they do not name the unit.
+    ///
+    /// The name is taken from the formatted amount 2.5 of the unit, so it is in the plural form
+    /// that such amounts take in this locale, and it may differ from the name used for other
+    /// amounts.  Fails with `U_NUMBER_SKELETON_SYNTAX_ERROR` if `unit` is not a valid unit
+    /// identifier, and with `U_UNSUPPORTED_ERROR` if the name is not a single piece of text next
+    /// to the number.  This is the case for `kilometer-per-hour` and `celsius` in the full name
+    /// width in `ja`, which are written around the number, and for `percent` in the short and
+    /// narrow widths, which is written with the percent sign of the number.
+    ///
+    /// Implements `unumf_openForSkeletonAndLocale`, `unumf_formatDouble` and
+    /// `unumf_resultNextFieldPosition` from ICU4C.
+    #[cfg(feature = "icu_version_67_plus")]
+    pub fn unit_display_name(
+        &self,
+        unit: &str,
+        width: sys::UNumberUnitWidth,
+    ) -> Result<String, common::Error> {
+        let width = match width {
+            sys::UNumberUnitWidth::UNUM_UNIT_WIDTH_NARROW => "narrow",
+            sys::UNumberUnitWidth::UNUM_UNIT_WIDTH_SHORT => "short",
+            sys::UNumberUnitWidth::UNUM_UNIT_WIDTH_FULL_NAME => "full-name",
+            _ => return Err(common::Error::Sys(UErrorCode::U_ILLEGAL_ARGUMENT_ERROR)),
+        };
+        let skeleton =
+            ustring::UChar::try_from(format!("unit/{} unit-width-{}", unit, width).as_str())?;
+        let asciiz_locale = self.as_c_str();
+        let mut status = common::Error::OK_CODE;
+        let formatter = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(unumf_openForSkeletonAndLocale)(
+                skeleton.as_c_ptr(),
+                skeleton.len() as i32,
+                asciiz_locale.as_ptr(),
+                &mut status,
+            )
+        };
+        let result = unsafe { versioned_function!(unumf_openResult)(&mut status) };
+        let name = common::Error::ok_or_warning(status).and_then(|_| {
+            let mut status = common::Error::OK_CODE;
+            unsafe { versioned_function!(unumf_formatDouble)(formatter, 2.5, result, &mut status) };
+            common::Error::ok_or_warning(status)?;
+            let mut formatted: Vec<sys::UChar> = vec![0; LOCALE_CAPACITY];
+            let mut len = unsafe {
+                versioned_function!(unumf_resultToString)(
+                    result,
+                    formatted.as_mut_ptr(),
+                    formatted.len() as i32,
+                    &mut status,
+                )
+            };
+            if status == UErrorCode::U_BUFFER_OVERFLOW_ERROR {
+                formatted.resize(len as usize, 0);
+                status = common::Error::OK_CODE;
+                len = unsafe {
+                    versioned_function!(unumf_resultToString)(
+                        result,
+                        formatted.as_mut_ptr(),
+                        formatted.len() as i32,
+                        &mut status,
+                    )
+                };
             }
-        }
+            common::Error::ok_or_warning(status)?;
+            formatted.truncate(len as usize);
+            let mut position = sys::UFieldPosition {
+                field: sys::UNumberFormatFields::UNUM_MEASURE_UNIT_FIELD as i32,
+                beginIndex: 0,
+                endIndex: 0,
+            };
+            let mut parts = vec![];
+            while unsafe {
+                versioned_function!(unumf_resultNextFieldPosition)(
+                    result,
+                    &mut position,
+                    &mut status,
+                )
+            } != 0
+            {
+                let part = &formatted[position.beginIndex as usize..position.endIndex as usize];
+                parts.push(
+                    String::from_utf16(part).map_err(|e| common::Error::wrapper(anyhow!(e)))?,
+                );
+            }
+            common::Error::ok_or_warning(status)?;
+            match parts.len() {
+                1 => Ok(parts.remove(0)),
+                _ => Err(common::Error::Sys(UErrorCode::U_UNSUPPORTED_ERROR)),
+            }
+        });
+        unsafe {
+            versioned_function!(unumf_closeResult)(result);
+            versioned_function!(unumf_close)(formatter);
+        };
+        name
+    }
 
-        self.language()
-            .cmp(&other.language())
-            .then_with(|| self.script().cmp(&other.script()))
-            .then_with(|| self.country().cmp(&other.country()))
-            .then_with(|| self.variant().cmp(&other.variant()))
-            .then_with(|| {
-                let mut self_keywords = self.keywords();
-                let mut other_keywords = other.keywords();
+    /// Returns the name of the time zone `zone_id`, such as `America/Los_Angeles`, in this
+    /// locale, such as "Pacific Time" in `en_US`.
+    ///
+    /// Where the locale has no name of the requested style, ICU falls back to another one, such
+    /// as "GMT-8" for the short standard name in `de`.  Fails with `U_ILLEGAL_ARGUMENT_ERROR` if
+    /// `zone_id` is not a time zone ID known to ICU.
+    ///
+    /// Implements `ucal_getTimeZoneDisplayName` for the standard and daylight saving time names,
+    /// and `udat_format` with the time zone patterns `vvvv`, `v` and `VVV` for the others.
+    pub fn time_zone_display_name(
+        &self,
+        zone_id: &str,
+        style: TimeZoneNameStyle,
+    ) -> Result<String, common::Error> {
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_canonical_time_zone_id,
+            LOCALE_CAPACITY,
+            [zone_id: *const sys::UChar, len: i32,],
+            [is_system_id: *mut sys::UBool,]
+        );
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_time_zone_display_name,
+            LOCALE_CAPACITY,
+            [
+                cal: *const sys::UCalendar,
+                type_: sys::UCalendarDisplayNameType,
+                locale: *const raw::c_char,
+            ],
+            []
+        );
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_format_date,
+            LOCALE_CAPACITY,
+            [format: *const sys::UDateFormat, date: sys::UDate,],
+            [position: *mut sys::UFieldPosition,]
+        );
 
-                while let Some(keyword_ordering) =
-                    compare_keywords(self, &self_keywords.next(), other, &other_keywords.next())
-                {
-                    match keyword_ordering {
-                        Ordering::Equal => {}
-                        unequal_ordering => {
-                            return unequal_ordering;
-                        }
+        let zone = ustring::UChar::try_from(zone_id)?;
+        // ICU would display an unknown zone ID as the "unknown" zone.
+        let mut is_system_id: sys::UBool = 0;
+        buffered_uchar_canonical_time_zone_id(
+            versioned_function!(ucal_getCanonicalTimeZoneID),
+            zone.as_c_ptr(),
+            zone.len() as i32,
+            &mut is_system_id,
+        )?;
+        let asciiz_locale = self.as_c_str();
+        let pattern = match style {
+            TimeZoneNameStyle::LongGeneric => "vvvv",
+            TimeZoneNameStyle::ShortGeneric => "v",
+            TimeZoneNameStyle::ExemplarLocation => "VVV",
+            TimeZoneNameStyle::LongStandard
+            | TimeZoneNameStyle::LongDaylight
+            | TimeZoneNameStyle::ShortStandard
+            | TimeZoneNameStyle::ShortDaylight => {
+                let type_ = match style {
+                    TimeZoneNameStyle::LongStandard => sys::UCalendarDisplayNameType::UCAL_STANDARD,
+                    TimeZoneNameStyle::LongDaylight => sys::UCalendarDisplayNameType::UCAL_DST,
+                    TimeZoneNameStyle::ShortStandard => {
+                        sys::UCalendarDisplayNameType::UCAL_SHORT_STANDARD
                     }
-                }
+                    _ => sys::UCalendarDisplayNameType::UCAL_SHORT_DST,
+                };
+                let calendar =
+                    ucal::UCalendar::new(zone_id, &self.repr, sys::UCalendarType::UCAL_GREGORIAN)?;
+                let name = buffered_uchar_time_zone_display_name(
+                    versioned_function!(ucal_getTimeZoneDisplayName),
+                    calendar.as_c_calendar(),
+                    type_,
+                    asciiz_locale.as_ptr(),
+                )?;
+                return String::try_from(&name);
+            }
+        };
+        let pattern = ustring::UChar::try_from(pattern)?;
+        let mut status = common::Error::OK_CODE;
+        let format = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(udat_open)(
+                sys::UDateFormatStyle::UDAT_PATTERN,
+                sys::UDateFormatStyle::UDAT_PATTERN,
+                asciiz_locale.as_ptr(),
+                zone.as_c_ptr(),
+                zone.len() as i32,
+                pattern.as_c_ptr(),
+                pattern.len() as i32,
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        let name = buffered_uchar_format_date(
+            versioned_function!(udat_format),
+            format,
+            ucal::get_now(),
+            ptr::null_mut(),
+        );
+        unsafe { versioned_function!(udat_close)(format) };
+        String::try_from(&name?)
+    }
 
-                // All keywords and values were identical (or there were none)
-                Ordering::Equal
+    /// Returns the abbreviated names of the eras of a calendar in this locale, in the order of
+    /// their numbers, such as `["BC", "AD"]` for the Gregorian calendar in `en`.
+    ///
+    /// `calendar` is a calendar type such as `gregorian`, `buddhist` or `japanese`, in its legacy
+    /// or BCP-47 spelling; if it is `None`, the calendar of this locale is used.  Fails with
+    /// `U_ILLEGAL_ARGUMENT_ERROR` if `calendar` is not a calendar type known to ICU.
+    ///
+    /// Implements `udat_countSymbols` and `udat_getSymbols` with `UDAT_ERAS` from ICU4C.
+    pub fn era_names(&self, calendar: Option<&str>) -> Result<Vec<String>, common::Error> {
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_date_symbol,
+            LOCALE_CAPACITY,
+            [
+                fmt: *const sys::UDateFormat,
+                type_: sys::UDateFormatSymbolType,
+                index: i32,
+            ],
+            []
+        );
+        let loc = match calendar {
+            Some(calendar) if !is_valid_keyword_value("calendar", calendar) => {
+                return Err(common::Error::wrapper(
+                    anyhow::Error::new(common::Error::Sys(UErrorCode::U_ILLEGAL_ARGUMENT_ERROR))
+                        .context(format!("unknown calendar {:?}", calendar)),
+                ));
+            }
+            Some(calendar) => {
+                let calendar = calendar.to_ascii_lowercase();
+                let calendar = to_legacy_type("calendar", &calendar).unwrap_or(calendar);
+                self.set_keyword_value("calendar", Some(&calendar))?
+            }
+            None => self.clone(),
+        };
+        let asciiz_locale = loc.as_c_str();
+        let mut status = common::Error::OK_CODE;
+        let fmt = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(udat_open)(
+                sys::UDateFormatStyle::UDAT_MEDIUM,
+                sys::UDateFormatStyle::UDAT_MEDIUM,
+                asciiz_locale.as_ptr(),
+                ptr::null(),
+                0,
+                ptr::null(),
+                0,
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        let type_ = sys::UDateFormatSymbolType::UDAT_ERAS;
+        let count = unsafe { versioned_function!(udat_countSymbols)(fmt, type_) };
+        let names = (0..count)
+            .map(|index| {
+                let name = buffered_uchar_date_symbol(
+                    versioned_function!(udat_getSymbols),
+                    fmt,
+                    type_,
+                    index,
+                )?;
+                String::try_from(&name)
             })
+            .collect();
+        unsafe { versioned_function!(udat_close)(fmt) };
+        names
     }
-}
 
-impl PartialOrd for ULoc {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// Returns the ISO 4217 code of the currency of the region of this locale.
+    ///
+    /// Implements `ucurr_forLocale` from ICU4C.
+    fn currency_code(&self) -> Result<String, common::Error> {
+        let asciiz_locale = self.as_c_str();
+        // ISO 4217 codes are three letters long.
+        let mut buf: Vec<sys::UChar> = vec![0; 4];
+        let mut status = common::Error::OK_CODE;
+        let len = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(ucurr_forLocale)(
+                asciiz_locale.as_ptr(),
+                buf.as_mut_ptr(),
+                buf.len() as i32,
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        buf.truncate(len as usize);
+        String::from_utf16(&buf).map_err(|e| common::Error::wrapper(anyhow!(e)))
     }
-}
 
-/// Gets the current system default locale.
-///
-/// Implements `uloc_getDefault` from ICU4C.
-pub fn get_default() -> ULoc {
-    let loc = unsafe { versioned_function!(uloc_getDefault)() };
-    let uloc_cstr = unsafe { ffi::CStr::from_ptr(loc) };
-    crate::ULoc::try_from(uloc_cstr).expect("could not convert default locale to ULoc")
-}
+    /// Returns a display name of the currency `currency_code` in this locale.
+    ///
+    /// `get_name` is called with the zero-terminated currency code, the ID of this locale, and
+    /// the length and status to fill in, and returns the name from ICU's static data.
+    fn currency_name(
+        &self,
+        currency_code: &str,
+        get_name: impl FnOnce(
+            *const sys::UChar,
+            *const raw::c_char,
+            &mut i32,
+            &mut UErrorCode,
+        ) -> *const sys::UChar,
+    ) -> Result<String, common::Error> {
+        let mut currency = ustring::UChar::try_from(currency_code)?;
+        currency.make_z();
+        let asciiz_locale = self.as_c_str();
+        let mut len: i32 = 0;
+        let mut status = common::Error::OK_CODE;
+        let name = get_name(
+            currency.as_c_ptr(),
+            asciiz_locale.as_ptr(),
+            &mut len,
+            &mut status,
+        );
+        common::Error::ok_or_warning(status)?;
+        if name.is_null() {
+            return Ok(String::new());
+        }
+        let name = unsafe { std::slice::from_raw_parts(name, len as usize) };
+        String::from_utf16(name).map_err(|e| common::Error::wrapper(anyhow!(e)))
+    }
 
-/// Sets the current default system locale.
-///
-/// Implements `uloc_setDefault` from ICU4C.
-pub fn set_default(loc: &ULoc) -> Result<(), common::Error> {
-    let mut status = common::Error::OK_CODE;
-    let asciiz = str_to_cstring(&loc.repr);
-    unsafe { versioned_function!(uloc_setDefault)(asciiz.as_ptr(), &mut status) };
-    common::Error::ok_or_warning(status)
-}
+    /// Formats a number with a number format of this locale in `style`.
+    ///
+    /// `format` is called with the number format, the destination buffer and the status, and
+    /// returns the full length of the result.  It is called a second time with a larger buffer
+    /// if the result did not fit into the first one.
+    fn format_number(
+        &self,
+        style: sys::UNumberFormatStyle,
+        format: impl Fn(*const sys::UNumberFormat, &mut [sys::UChar], &mut UErrorCode) -> i32,
+    ) -> Result<String, common::Error> {
+        let asciiz_locale = self.as_c_str();
+        let mut status = common::Error::OK_CODE;
+        let fmt = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(unum_open)(
+                style,
+                ptr::null(),
+                0,
+                asciiz_locale.as_ptr(),
+                ptr::null_mut(),
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        let mut dest: Vec<sys::UChar> = vec![0; LOCALE_CAPACITY];
+        let mut status = common::Error::OK_CODE;
+        let mut full_len = format(fmt, &mut dest, &mut status);
+        if status == UErrorCode::U_BUFFER_OVERFLOW_ERROR {
+            dest.resize(full_len as usize, 0);
+            status = common::Error::OK_CODE;
+            full_len = format(fmt, &mut dest, &mut status);
+        }
+        unsafe { versioned_function!(unum_close)(fmt) };
+        common::Error::ok_or_warning(status)?;
+        dest.truncate(full_len as usize);
+        String::from_utf16(&dest).map_err(|e| common::Error::wrapper(anyhow!(e)))
+    }
 
-/// Implements `uloc_acceptLanguage` from ICU4C.
-pub fn accept_language(
-    accept_list: impl IntoIterator<Item = impl Into<ULoc>>,
-    available_locales: impl IntoIterator<Item = impl Into<ULoc>>,
-) -> Result<(Option<ULoc>, UAcceptResult), common::Error> {
-    buffered_string_method_with_retry!(
-        buffered_string_uloc_accept_language,
-        LOCALE_CAPACITY,
-        [],
+    /// Returns the date and time pattern of this locale that best matches `skeleton`.
+    ///
+    /// A skeleton lists the fields to show, such as `yMMMd` for year, abbreviated month and
+    /// day; the pattern arranges them the way this locale does, such as `MMM d, y` in `en`, or
+    /// `d. MMM y` in `de`.
+    ///
+    /// Implements `udatpg_open` and `udatpg_getBestPattern` from ICU4C.
+    pub fn date_pattern(&self, skeleton: &str) -> Result<String, common::Error> {
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_best_pattern,
+            LOCALE_CAPACITY,
+            [
+                dtpg: *mut sys::UDateTimePatternGenerator,
+                skeleton: *const sys::UChar,
+                length: i32,
+            ],
+            []
+        );
+        let asciiz_locale = self.as_c_str();
+        let skeleton = ustring::UChar::try_from(skeleton)?;
+        let mut status = common::Error::OK_CODE;
+        let dtpg = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(udatpg_open)(asciiz_locale.as_ptr(), &mut status)
+        };
+        common::Error::ok_or_warning(status)?;
+        let uchar = buffered_uchar_best_pattern(
+            versioned_function!(udatpg_getBestPattern),
+            dtpg,
+            skeleton.as_c_ptr(),
+            skeleton.len() as i32,
+        );
+        unsafe { versioned_function!(udatpg_close)(dtpg) };
+        String::try_from(&uchar?)
+    }
+
+    /// Returns the day on which the week starts in this locale, such as Sunday in `en_US`, or
+    /// Monday in `fr_FR`.
+    pub fn first_day_of_week(&self) -> Result<sys::UCalendarDaysOfWeek, common::Error> {
+        let calendar = ucal::UCalendar::new("UTC", self.label(), sys::UCalendarType::UCAL_DEFAULT)?;
+        let day = calendar.get_attribute(sys::UCalendarAttribute::UCAL_FIRST_DAY_OF_WEEK);
+        use sys::UCalendarDaysOfWeek::*;
         [
-            out_result: *mut UAcceptResult,
-            accept_list: *mut *const ::std::os::raw::c_char,
-            accept_list_count: i32,
-            available_locales: *mut UEnumeration,
+            UCAL_SUNDAY,
+            UCAL_MONDAY,
+            UCAL_TUESDAY,
+            UCAL_WEDNESDAY,
+            UCAL_THURSDAY,
+            UCAL_FRIDAY,
+            UCAL_SATURDAY,
         ]
-    );
+        .iter()
+        .copied()
+        .find(|weekday| *weekday as i32 == day)
+        .ok_or_else(|| common::Error::wrapper(anyhow!("not a day of the week: {}", day)))
+    }
 
-    let mut accept_result: UAcceptResult = UAcceptResult::ULOC_ACCEPT_FAILED;
-    let mut accept_list_cstrings: Vec<ffi::CString> = vec![];
-    // This is mutable only to satisfy the missing `const`s in the ICU4C API.
-    let mut accept_list: Vec<*const raw::c_char> = accept_list
-        .into_iter()
-        .map(|item| {
-            let uloc: ULoc = item.into();
-            accept_list_cstrings.push(uloc.as_c_str());
-            accept_list_cstrings
-                .last()
-                .expect("non-empty list")
-                .as_ptr()
-        })
-        .collect();
+    /// Returns the calendar systems commonly used in this locale, in order of preference, such
+    /// as `gregorian` and `buddhist` for `th_TH`.
+    ///
+    /// Implements `ucal_getKeywordValuesForLocale` from ICU4C.
+    pub fn available_calendars(&self) -> Result<Vec<String>, common::Error> {
+        let asciiz_locale = self.as_c_str();
+        let mut status = common::Error::OK_CODE;
+        let raw_enum = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(ucal_getKeywordValuesForLocale)(
+                b"calendar\0".as_ptr() as *const raw::c_char,
+                asciiz_locale.as_ptr(),
+                true as sys::UBool,
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        if raw_enum.is_null() {
+            return Ok(vec![]);
+        }
+        unsafe { Enumeration::from_raw_parts(None, raw_enum) }.collect()
+    }
 
-    let available_locales: Vec<ULoc> = available_locales
-        .into_iter()
-        .map(|item| item.into())
+    /// Implements `uloc_openKeywords()` from ICU4C.
+    ///
+    /// The keywords are yielded in ascending order of their names.  ICU itself sorts the
+    /// keywords when canonicalizing a locale, but `uloc_openKeywords()` does not document an
+    /// order, so the order is enforced here.
+    ///
+    /// ICU enumerations can only be traversed forward, so all keywords are read into a buffer
+    /// when this is called.  In exchange, the returned iterator can also be traversed in reverse.
+    pub fn keywords(&self) -> impl DoubleEndedIterator<Item = String> {
+        let mut keywords: Vec<String> = rust_icu_uenum::uloc_open_keywords(&self.repr)
+            .unwrap()
+            .map(|result| result.unwrap())
+            .collect();
+        keywords.sort();
+        keywords.into_iter()
+    }
+
+    /// Returns all keywords of this locale and their values, keyed by keyword name.
+    ///
+    /// Like [keywords](ULoc::keywords), iterating over the map yields the keywords in ascending
+    /// order of their names.
+    pub fn keywords_map(&self) -> Result<BTreeMap<String, String>, common::Error> {
+        let mut map = BTreeMap::new();
+        for keyword in self.keywords() {
+            if let Some(value) = self.keyword_value(&keyword)? {
+                map.insert(keyword, value);
+            }
+        }
+        Ok(map)
+    }
+
+    /// Calls `f` with the name and value of each keyword of this locale, in ascending order of
+    /// the names.
+    ///
+    /// Unlike [keywords_map](ULoc::keywords_map), this does not collect the keywords into a map,
+    /// which suits streaming them into another structure.  Stops at, and returns, the first
+    /// error returned by `f`.
+    pub fn for_each_keyword(
+        &self,
+        mut f: impl FnMut(&str, &str) -> Result<(), common::Error>,
+    ) -> Result<(), common::Error> {
+        for keyword in self.keywords() {
+            if let Some(value) = self.keyword_value(&keyword)? {
+                f(&keyword, &value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the keywords of this locale and their values as the keys and types of the `-u-`
+    /// extension of its language tag, such as `{"ca": "gregory", "nu": "latn"}` for
+    /// `en-US-u-ca-gregory-nu-latn`.
+    ///
+    /// Keywords that have no BCP-47 form, and values that can not be converted, are left out.
+    pub fn unicode_extension_keywords(&self) -> Result<BTreeMap<String, String>, common::Error> {
+        Ok(self
+            .keywords_map()?
+            .into_iter()
+            .filter_map(|(keyword, value)| {
+                let key = to_unicode_locale_key(&keyword)?;
+                let unicode_type = to_unicode_locale_type(&keyword, &value)?;
+                Some((key, unicode_type))
+            })
+            .collect())
+    }
+
+    /// Implements `icu::Locale::getUnicodeKeywords()` from the C++ API.
+    pub fn unicode_keywords(&self) -> impl Iterator<Item = String> {
+        self.keywords().filter_map(|s| to_unicode_locale_key(&s))
+    }
+
+    /// Implements `uloc_getKeywordValue()` from ICU4C.
+    ///
+    /// Keyword names are case insensitive: `Calendar` and `calendar` name the same keyword.
+    pub fn keyword_value(&self, keyword: &str) -> Result<Option<String>, common::Error> {
+        buffered_string_method_with_retry!(
+            buffered_string_keyword_value,
+            LOCALE_CAPACITY,
+            [
+                locale_id: *const raw::c_char,
+                keyword_name: *const raw::c_char,
+            ],
+            []
+        );
+        let locale_id = self.as_c_str();
+        let keyword_name = str_to_cstring(&keyword.to_ascii_lowercase());
+        buffered_string_keyword_value(
+            versioned_function!(uloc_getKeywordValue),
+            locale_id.as_ptr(),
+            keyword_name.as_ptr(),
+        )
+        .map(|value| if value.is_empty() { None } else { Some(value) })
+    }
+
+    /// Implements `uloc_getKeywordValue()` from ICU4C, writing into a caller-provided buffer.
+    ///
+    /// On success `buf` holds exactly the bytes of the value, without a terminating NUL, and the
+    /// value's length is returned; `None` is returned, and `buf` left empty, if `keyword` is not
+    /// set.  The value is written into the buffer's existing allocation whenever it fits, so a
+    /// buffer reused across calls stops allocating once it has grown to the longest value read.
+    ///
+    /// Keyword names are case insensitive, as for [keyword_value](ULoc::keyword_value).
+    pub fn keyword_value_into(
+        &self,
+        keyword: &str,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<usize>, common::Error> {
+        let locale_id = self.as_c_str();
+        let keyword_name = str_to_cstring(&keyword.to_ascii_lowercase());
+        buf.clear();
+        if buf.capacity() == 0 {
+            buf.reserve(LOCALE_CAPACITY);
+        }
+        loop {
+            buf.resize(buf.capacity(), 0);
+            let mut status = common::Error::OK_CODE;
+            let full_len: i32 = unsafe {
+                assert!(common::Error::is_ok(status));
+                versioned_function!(uloc_getKeywordValue)(
+                    locale_id.as_ptr(),
+                    keyword_name.as_ptr(),
+                    buf.as_mut_ptr() as *mut raw::c_char,
+                    buf.len() as i32,
+                    &mut status,
+                )
+            };
+            let full_len: usize = full_len.try_into().map_err(common::Error::wrapper)?;
+            if status == UErrorCode::U_BUFFER_OVERFLOW_ERROR || full_len > buf.len() {
+                buf.clear();
+                buf.reserve(full_len + 1);
+                continue;
+            }
+            common::Error::ok_or_warning(status)?;
+            buf.truncate(full_len);
+            return Ok(if full_len == 0 { None } else { Some(full_len) });
+        }
+    }
+
+    /// Implements `uloc_setKeywordValue()` from ICU4C.
+    ///
+    /// Returns a copy of this locale with `keyword` set to `value`, or with `keyword` removed if
+    /// `value` is `None`.  Keyword names are case insensitive, as for
+    /// [keyword_value](ULoc::keyword_value); the keyword is stored under its lowercase name.
+    pub fn set_keyword_value(
+        &self,
+        keyword: &str,
+        value: Option<&str>,
+    ) -> Result<ULoc, common::Error> {
+        let keyword_name = str_to_cstring(&keyword.to_ascii_lowercase());
+        let keyword_value = value.map(str_to_cstring);
+        let keyword_value_ptr = keyword_value
+            .as_ref()
+            .map_or(ptr::null(), |value| value.as_ptr());
+        // uloc_setKeywordValue edits the locale ID in place, so the buffer starts out as a copy
+        // of this locale, and is refilled from it if it turns out to be too small.
+        let mut capacity = std::cmp::max(LOCALE_CAPACITY, self.repr.len() + 1);
+        loop {
+            let mut buf: Vec<u8> = vec![0; capacity];
+            buf[..self.repr.len()].copy_from_slice(self.repr.as_bytes());
+            let mut status = common::Error::OK_CODE;
+            let full_len: i32 = unsafe {
+                assert!(common::Error::is_ok(status));
+                versioned_function!(uloc_setKeywordValue)(
+                    keyword_name.as_ptr(),
+                    keyword_value_ptr,
+                    buf.as_mut_ptr() as *mut raw::c_char,
+                    capacity as i32,
+                    &mut status,
+                )
+            };
+            let full_len: usize = full_len.try_into().map_err(common::Error::wrapper)?;
+            if status == UErrorCode::U_BUFFER_OVERFLOW_ERROR || full_len >= capacity {
+                capacity = full_len + 1;
+                continue;
+            }
+            common::Error::ok_or_warning(status)?;
+            buf.truncate(full_len);
+            let repr = String::from_utf8(buf).map_err(common::Error::wrapper)?;
+            return Ok(ULoc { repr });
+        }
+    }
+
+    /// Returns a copy of this locale with all of its keywords removed.
+    ///
+    /// Each keyword is removed in turn with [set_keyword_value](ULoc::set_keyword_value), so the
+    /// language, script, country and variant are kept exactly as they are.
+    pub fn clear_all_keywords(&self) -> Result<ULoc, common::Error> {
+        let mut cleared = self.clone();
+        for keyword in self.keywords() {
+            cleared = cleared.set_keyword_value(&keyword, None)?;
+        }
+        Ok(cleared)
+    }
+
+    /// Compares the keywords of this locale with those of `other`, such as a previous and a new
+    /// locale preference.
+    ///
+    /// Keywords are compared by name and value as ICU stores them, so `en@calendar=japanese` and
+    /// `en@ca=japanese` do not compare equal; construct both locales the same way.
+    pub fn keyword_diff(&self, other: &ULoc) -> Result<KeywordDiff, common::Error> {
+        let before = self.keywords_map()?;
+        let after = other.keywords_map()?;
+        let mut diff = KeywordDiff::default();
+        for (keyword, value) in &before {
+            match after.get(keyword) {
+                None => {
+                    diff.removed.insert(keyword.clone());
+                }
+                Some(new_value) if new_value != value => {
+                    diff.changed.insert(keyword.clone());
+                }
+                Some(_) => {}
+            }
+        }
+        diff.added.extend(
+            after
+                .into_keys()
+                .filter(|keyword| !before.contains_key(keyword)),
+        );
+        Ok(diff)
+    }
+
+    /// Returns a copy of this locale with the keywords of `other` added to it.
+    ///
+    /// Keywords that only `other` has are always added.  For keywords that both locales have,
+    /// the value of `other` replaces that of this locale if `overwrite` is true, and is ignored
+    /// otherwise.  The language, script, country and variant of `other` are ignored.
+    pub fn with_keywords_from(&self, other: &ULoc, overwrite: bool) -> Result<ULoc, common::Error> {
+        let mut merged = self.clone();
+        for (keyword, value) in other.keywords_map()? {
+            if overwrite || !self.has_keyword(&keyword)? {
+                merged = merged.set_keyword_value(&keyword, Some(&value))?;
+            }
+        }
+        Ok(merged)
+    }
+
+    /// Implements `uloc_getBaseName` from ICU4C.
+    ///
+    /// The base name is this locale without its keywords, and so without the extensions of its
+    /// language tag, such as `th_TH` for `th-TH-u-ca-buddhist`.
+    pub fn base_name(&self) -> Result<ULoc, common::Error> {
+        self.call_buffered_string_method(versioned_function!(uloc_getBaseName))
+            .map(|repr| ULoc { repr })
+    }
+
+    /// Returns a copy of this locale with deprecated keyword values replaced by their modern
+    /// equivalents, such as `calendar=islamicc` by `calendar=islamic-civil`.
+    ///
+    /// Each value is round-tripped through [to_unicode_locale_type] and [to_legacy_type], so the
+    /// replacements are exactly the type aliases in ICU's key/type data; values that ICU does not
+    /// know of are kept as they are.  Each replaced value is logged at the `trace` level.
+    pub fn migrate_keywords(&self) -> Result<ULoc, common::Error> {
+        let mut migrated = self.clone();
+        for (keyword, value) in self.keywords_map()? {
+            let modern = to_unicode_locale_type(&keyword, &value)
+                .and_then(|unicode_value| to_legacy_type(&keyword, &unicode_value));
+            match modern {
+                Some(modern) if modern != value => {
+                    trace!("migrating keyword {}: {} -> {}", keyword, value, modern);
+                    migrated = migrated.set_keyword_value(&keyword, Some(&modern))?;
+                }
+                _ => {}
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// Returns true if this locale has a value set for `keyword`.
+    ///
+    /// Gives the same answer as `keyword_value(keyword).map(|v| v.is_some())`, but stops at the
+    /// first matching keyword name instead of retrieving the value.
+    pub fn has_keyword(&self, keyword: &str) -> Result<bool, common::Error> {
+        for name in rust_icu_uenum::uloc_open_keywords(&self.repr)? {
+            if name?.eq_ignore_ascii_case(keyword) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Implements `icu::Locale::getUnicodeKeywordValue()` from ICU4C.
+    pub fn unicode_keyword_value(
+        &self,
+        unicode_keyword: &str,
+    ) -> Result<Option<String>, common::Error> {
+        let legacy_keyword = to_legacy_key(unicode_keyword);
+        match legacy_keyword {
+            Some(legacy_keyword) => match self.keyword_value(&legacy_keyword) {
+                Ok(Some(legacy_value)) => {
+                    Ok(to_unicode_locale_type(&legacy_keyword, &legacy_value))
+                }
+                Ok(None) => Ok(None),
+                Err(e) => Err(e),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the name of this locale, suitable for display in `display_locale`.
+    ///
+    /// Implements `uloc_getDisplayName` from ICU4C.
+    pub fn display_name(&self, display_locale: &ULoc) -> Result<String, common::Error> {
+        self.call_buffered_display_method(versioned_function!(uloc_getDisplayName), display_locale)
+    }
+
+    /// Like [display_name](ULoc::display_name), but fails if ICU had no display data specific
+    /// to `display_locale`.
+    ///
+    /// When the data came from a parent of `display_locale`, the error is
+    /// `U_USING_FALLBACK_WARNING`, and when `display_locale` had no data at all, so that the
+    /// default locale's data was used instead, the error is `U_USING_DEFAULT_WARNING`; both can
+    /// be read with [common::Error::error_code].  Note that data is often shared with a parent:
+    /// `en_US` gets its display names from `en`, for example, so the display name in `en_US` is
+    /// an error while the one in `en` is not.
+    pub fn display_name_strict(&self, display_locale: &ULoc) -> Result<String, common::Error> {
+        let (name, status) = self.display_name_with_status(display_locale)?;
+        if status == UErrorCode::U_USING_FALLBACK_WARNING
+            || status == UErrorCode::U_USING_DEFAULT_WARNING
+        {
+            return Err(common::Error::Sys(status));
+        }
+        Ok(name)
+    }
+
+    /// Returns the name of this locale as displayed in `display_locale`, together with the
+    /// status ICU reported for it, which may be a warning.
+    ///
+    /// Implements `uloc_getDisplayName` from ICU4C.
+    fn display_name_with_status(
+        &self,
+        display_locale: &ULoc,
+    ) -> Result<(String, UErrorCode), common::Error> {
+        let locale_id = self.as_c_str();
+        let display_locale_id = display_locale.as_c_str();
+        let mut buf: Vec<sys::UChar> = vec![0; LOCALE_CAPACITY];
+        loop {
+            let mut status = common::Error::OK_CODE;
+            let full_len: i32 = unsafe {
+                assert!(common::Error::is_ok(status));
+                versioned_function!(uloc_getDisplayName)(
+                    locale_id.as_ptr(),
+                    display_locale_id.as_ptr(),
+                    buf.as_mut_ptr(),
+                    buf.len() as i32,
+                    &mut status,
+                )
+            };
+            let full_len: usize = full_len.try_into().map_err(common::Error::wrapper)?;
+            if status == UErrorCode::U_BUFFER_OVERFLOW_ERROR || full_len > buf.len() {
+                buf.resize(full_len, 0);
+                continue;
+            }
+            common::Error::ok_or_warning(status)?;
+            buf.truncate(full_len);
+            return Ok((String::try_from(&ustring::UChar::from(buf))?, status));
+        }
+    }
+
+    /// Returns the name of this locale as displayed in the first of `display_locales` that has
+    /// display data of its own.
+    ///
+    /// Each display locale is tried in turn, and the first name that ICU produced without a
+    /// `U_USING_FALLBACK_WARNING` or `U_USING_DEFAULT_WARNING` is returned, as with
+    /// [display_name_strict](ULoc::display_name_strict).  Data inherited from a parent counts as
+    /// a fallback, so `en_US` is passed over in favor of a later display locale.  If no display
+    /// locale has data of its own, the name from the last one is returned anyway.  Fails if
+    /// `display_locales` is empty.
+    pub fn display_name_preferring(
+        &self,
+        display_locales: &[ULoc],
+    ) -> Result<String, common::Error> {
+        let mut last_name = None;
+        for display_locale in display_locales {
+            let (name, status) = self.display_name_with_status(display_locale)?;
+            if status != UErrorCode::U_USING_FALLBACK_WARNING
+                && status != UErrorCode::U_USING_DEFAULT_WARNING
+            {
+                return Ok(name);
+            }
+            last_name = Some(name);
+        }
+        last_name.ok_or(common::Error::Sys(UErrorCode::U_ILLEGAL_ARGUMENT_ERROR))
+    }
+
+    /// Returns the name of this locale, suitable for display in the current default locale.
+    ///
+    /// See [display_name](ULoc::display_name) and [get_default].
+    pub fn display_name_in_default(&self) -> Result<String, common::Error> {
+        self.display_name(&get_default())
+    }
+
+    /// Implements `uloc_getDisplayLanguage` from ICU4C.
+    pub fn display_language(&self, display_locale: &ULoc) -> Result<String, common::Error> {
+        self.call_buffered_display_method(
+            versioned_function!(uloc_getDisplayLanguage),
+            display_locale,
+        )
+    }
+
+    /// Returns the name of this locale's language, suitable for display in the current default
+    /// locale.
+    ///
+    /// See [display_language](ULoc::display_language) and [get_default].
+    pub fn display_language_in_default(&self) -> Result<String, common::Error> {
+        self.display_language(&get_default())
+    }
+
+    /// Implements `uloc_getDisplayScript` from ICU4C.
+    pub fn display_script(&self, display_locale: &ULoc) -> Result<String, common::Error> {
+        self.call_buffered_display_method(
+            versioned_function!(uloc_getDisplayScript),
+            display_locale,
+        )
+    }
+
+    /// Returns the name of this locale's script, suitable for display in the current default
+    /// locale.
+    ///
+    /// See [display_script](ULoc::display_script) and [get_default].
+    pub fn display_script_in_default(&self) -> Result<String, common::Error> {
+        self.display_script(&get_default())
+    }
+
+    /// Implements `uloc_getDisplayCountry` from ICU4C.
+    pub fn display_country(&self, display_locale: &ULoc) -> Result<String, common::Error> {
+        self.call_buffered_display_method(
+            versioned_function!(uloc_getDisplayCountry),
+            display_locale,
+        )
+    }
+
+    /// Returns the name of this locale's country, suitable for display in the current default
+    /// locale.
+    ///
+    /// See [display_country](ULoc::display_country) and [get_default].
+    pub fn display_country_in_default(&self) -> Result<String, common::Error> {
+        self.display_country(&get_default())
+    }
+
+    /// Returns the name of the region of this locale, as displayed in `display_locale`, such as
+    /// `États-Unis` for `en_US` in `fr`.
+    ///
+    /// Unlike [display_country](ULoc::display_country), which returns an empty string for
+    /// locales without a region, this fails if this locale has no region subtag.
+    pub fn region_display_name(&self, display_locale: &ULoc) -> Result<String, common::Error> {
+        let region = self.country().ok_or_else(|| {
+            common::Error::wrapper(anyhow!("locale {:?} has no region subtag", self.repr))
+        })?;
+        LocaleDisplayNames::try_new(display_locale, sys::UDialectHandling::ULDN_STANDARD_NAMES)?
+            .region_name(&region)
+    }
+
+    /// Implements `uloc_getDisplayVariant` from ICU4C.
+    pub fn display_variant(&self, display_locale: &ULoc) -> Result<String, common::Error> {
+        self.call_buffered_display_method(
+            versioned_function!(uloc_getDisplayVariant),
+            display_locale,
+        )
+    }
+
+    /// Returns the name of this locale's variant, suitable for display in the current default
+    /// locale.
+    ///
+    /// See [display_variant](ULoc::display_variant) and [get_default].
+    pub fn display_variant_in_default(&self) -> Result<String, common::Error> {
+        self.display_variant(&get_default())
+    }
+
+    /// Compares this locale and `other` by their names as displayed in `display_locale`.
+    ///
+    /// The names are compared with a collator for `display_locale`, so the result matches the
+    /// order that users of `display_locale` would expect in a sorted list, rather than the byte
+    /// order of the names.
+    pub fn cmp_by_display_name(
+        &self,
+        other: &ULoc,
+        display_locale: &ULoc,
+    ) -> Result<Ordering, common::Error> {
+        let collator = ucol::UCollator::try_from(display_locale.label())?;
+        collator.strcoll_utf8(
+            self.display_name(display_locale)?,
+            other.display_name(display_locale)?,
+        )
+    }
+
+    /// Returns the valid and the actual locale of the `resource` data that ICU loads for this
+    /// locale.
+    ///
+    /// The valid locale is the most specific locale ICU has any data for; the actual locale is
+    /// the one the data really came from, which reveals silent fallbacks.  For example, `de_AT`
+    /// is valid for collation, but its collation data comes from the root locale.  The root
+    /// locale is returned as the empty locale, like [ULoc::parent] does.
+    pub fn resolved_locale(&self, resource: ResourceKind) -> Result<(ULoc, ULoc), common::Error> {
+        let (valid, actual) = match resource {
+            ResourceKind::Collation => {
+                let collator = ucol::UCollator::try_from(self.label())?;
+                (
+                    collator.get_locale_by_type(sys::ULocDataLocaleType::ULOC_VALID_LOCALE)?,
+                    collator.get_locale_by_type(sys::ULocDataLocaleType::ULOC_ACTUAL_LOCALE)?,
+                )
+            }
+            ResourceKind::NumberFormat => self.number_format_locales()?,
+            ResourceKind::Calendar => {
+                let calendar =
+                    ucal::UCalendar::new("UTC", self.label(), sys::UCalendarType::UCAL_DEFAULT)?;
+                (
+                    calendar.get_locale_by_type(sys::ULocDataLocaleType::ULOC_VALID_LOCALE)?,
+                    calendar.get_locale_by_type(sys::ULocDataLocaleType::ULOC_ACTUAL_LOCALE)?,
+                )
+            }
+        };
+        let to_uloc = |id: String| ULoc::try_from(if id == "root" { "" } else { id.as_str() });
+        Ok((to_uloc(valid)?, to_uloc(actual)?))
+    }
+
+    /// Returns the valid and the actual locale of the number format of this locale.
+    ///
+    /// Implements `unum_open`, `unum_getLocaleByType` and `unum_close` from ICU4C.
+    fn number_format_locales(&self) -> Result<(String, String), common::Error> {
+        let asciiz_locale = self.as_c_str();
+        let mut status = common::Error::OK_CODE;
+        let fmt = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(unum_open)(
+                sys::UNumberFormatStyle::UNUM_DECIMAL,
+                ptr::null(),
+                0,
+                asciiz_locale.as_ptr(),
+                ptr::null_mut(),
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        let locale_of = |type_| -> Result<String, common::Error> {
+            let mut status = common::Error::OK_CODE;
+            let locale =
+                unsafe { versioned_function!(unum_getLocaleByType)(fmt, type_, &mut status) };
+            common::Error::ok_or_warning(status)?;
+            if locale.is_null() {
+                return Ok(String::new());
+            }
+            Ok(unsafe { ffi::CStr::from_ptr(locale) }.to_str()?.to_string())
+        };
+        let locales = locale_of(sys::ULocDataLocaleType::ULOC_VALID_LOCALE).and_then(|valid| {
+            Ok((
+                valid,
+                locale_of(sys::ULocDataLocaleType::ULOC_ACTUAL_LOCALE)?,
+            ))
+        });
+        unsafe { versioned_function!(unum_close)(fmt) };
+        locales
+    }
+
+    /// Returns true if this locale is among the available locales of `service`.
+    ///
+    /// Implements `ucol_getAvailable`, `udat_getAvailable`, `unum_getAvailable` and
+    /// `ubrk_getAvailable` from ICU4C.  Each service lists only the locales it has data of its
+    /// own for, so `en_GB` is available for date formatting, but not for collation, where it
+    /// uses the data of `en`.  The keywords of this locale are ignored.
+    pub fn is_available_for(&self, service: IcuService) -> bool {
+        let (count, get_available): (
+            unsafe extern "C" fn() -> i32,
+            unsafe extern "C" fn(i32) -> *const raw::c_char,
+        ) = match service {
+            IcuService::Collator => (
+                versioned_function!(ucol_countAvailable),
+                versioned_function!(ucol_getAvailable),
+            ),
+            IcuService::DateFormat => (
+                versioned_function!(udat_countAvailable),
+                versioned_function!(udat_getAvailable),
+            ),
+            IcuService::NumberFormat => (
+                versioned_function!(unum_countAvailable),
+                versioned_function!(unum_getAvailable),
+            ),
+            IcuService::BreakIterator => (
+                versioned_function!(ubrk_countAvailable),
+                versioned_function!(ubrk_getAvailable),
+            ),
+        };
+        let base = match self.base_name() {
+            Ok(base) => base,
+            Err(_) => return false,
+        };
+        let count = unsafe { count() };
+        (0..count).any(|index| {
+            let loc = unsafe { get_available(index) };
+            !loc.is_null() && base.eq_c_str(unsafe { ffi::CStr::from_ptr(loc) })
+        })
+    }
+
+    /// Returns the script reordering codes of this locale's default collation.
+    ///
+    /// The codes are `UScriptCode` or `UColReorderCode` values, in the order in which the
+    /// collation sorts them before all other scripts; for example `ja` puts Latin, Kana and Han
+    /// first.  The result is empty if the collation does not reorder scripts.
+    pub fn collation_reorder_codes(&self) -> Result<Vec<i32>, common::Error> {
+        ucol::UCollator::try_from(self.label())?.get_reorder_codes()
+    }
+
+    /// Returns the sort key of `text` for the collation of this locale, at `strength`.
+    ///
+    /// Sort keys compare byte by byte the way their texts compare with that collation, so
+    /// texts that only differ in ways below `strength` have equal keys; at
+    /// `UCOL_PRIMARY`, `café` and `cafe` do.
+    pub fn collation_key(
+        &self,
+        text: &str,
+        strength: sys::UCollationStrength,
+    ) -> Result<Vec<u8>, common::Error> {
+        let mut collator = ucol::UCollator::try_from(self.label())?;
+        collator.set_strength(strength);
+        Ok(collator.get_sort_key(&ustring::UChar::try_from(text)?))
+    }
+
+    /// Compares `a` and `b` with the collation of this locale, at `strength`.
+    ///
+    /// The collation can be selected with the `collation` keyword of this locale, as in
+    /// `de@collation=phonebook`.
+    pub fn compare(
+        &self,
+        a: &str,
+        b: &str,
+        strength: sys::UCollationStrength,
+    ) -> Result<Ordering, common::Error> {
+        let mut collator = ucol::UCollator::try_from(self.label())?;
+        collator.set_strength(strength);
+        collator.strcoll_utf8(a, b)
+    }
+
+    /// Joins `items` into a single string using the list patterns of this locale, such as
+    /// `A, B, and C` in `en`, or `A, B und C` in `de`.
+    pub fn format_list(&self, items: &[&str]) -> Result<String, common::Error> {
+        ulistformatter::UListFormatter::try_new(self.label())?.format(items)
+    }
+
+    /// Returns the plural category of the number `n` in this locale, one of `zero`, `one`, `two`,
+    /// `few`, `many` and `other`.
+    pub fn plural_category(&self, n: f64, kind: PluralKind) -> Result<String, common::Error> {
+        upluralrules::UPluralRules::try_new_styled(self.label(), kind.into())?.select(n)
+    }
+
+    /// Returns the plural categories used by this locale, such as only `other` for `ja`.
+    ///
+    /// A message with plural forms needs one form for each of these categories to be complete.
+    pub fn plural_keywords(&self, kind: PluralKind) -> Result<Vec<String>, common::Error> {
+        upluralrules::UPluralRules::try_new_styled(self.label(), kind.into())?
+            .get_keywords()?
+            .collect()
+    }
+
+    /// Returns the direction of `text` when rendered as a paragraph in this locale.
+    ///
+    /// The paragraph direction is taken from the first strong character of `text`, and from the
+    /// writing direction of this locale if `text` has none.  The result is `UBIDI_LTR` or
+    /// `UBIDI_RTL` if all of `text` runs in one direction, and `UBIDI_MIXED` otherwise.
+    ///
+    /// Implements `uloc_isRightToLeft`, `ubidi_setPara` and `ubidi_getDirection` from ICU4C.
+    pub fn base_direction(&self, text: &str) -> sys::UBiDiDirection {
+        // The pseudo-levels `UBIDI_DEFAULT_LTR` and `UBIDI_DEFAULT_RTL` from `ubidi.h`.
+        const DEFAULT_LTR: sys::UBiDiLevel = 0xfe;
+        const DEFAULT_RTL: sys::UBiDiLevel = 0xff;
+        let asciiz_locale = self.as_c_str();
+        let right_to_left =
+            unsafe { versioned_function!(uloc_isRightToLeft)(asciiz_locale.as_ptr()) } != 0;
+        let para_level = if right_to_left {
+            DEFAULT_RTL
+        } else {
+            DEFAULT_LTR
+        };
+        let text = ustring::UChar::try_from(text).expect("a str is always valid UTF-8");
+        let mut status = common::Error::OK_CODE;
+        // Unsafety note: the bidi object only references `text`, and is closed before `text` is
+        // dropped.
+        unsafe {
+            let bidi = versioned_function!(ubidi_open)();
+            assert!(!bidi.is_null(), "ubidi_open: out of memory");
+            versioned_function!(ubidi_setPara)(
+                bidi,
+                text.as_c_ptr(),
+                text.len() as i32,
+                para_level,
+                ptr::null_mut(),
+                &mut status,
+            );
+            let direction = versioned_function!(ubidi_getDirection)(bidi);
+            versioned_function!(ubidi_close)(bidi);
+            assert!(common::Error::is_ok(status), "ubidi_setPara: {:?}", status);
+            direction
+        }
+    }
+
+    /// Returns the byte offsets of the word boundaries in `text`, using the word break rules of
+    /// this locale.
+    ///
+    /// The offsets are in ascending order, and include `0` and `text.len()`.  Words in scripts
+    /// written without spaces, such as Thai, are told apart using ICU's dictionaries.
+    ///
+    /// Implements `ubrk_open`, `ubrk_first` and `ubrk_next` from ICU4C.
+    pub fn word_boundaries(&self, text: &str) -> Result<Vec<usize>, common::Error> {
+        // `UBRK_DONE` from `ubrk.h`.
+        const DONE: i32 = -1;
+        let asciiz_locale = self.as_c_str();
+        let uchar = ustring::UChar::try_from(text)?;
+        let mut status = common::Error::OK_CODE;
+        // Unsafety note: the break iterator only references `uchar`, and is closed before
+        // `uchar` is dropped.
+        let rep = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(ubrk_open)(
+                sys::UBreakIteratorType::UBRK_WORD,
+                asciiz_locale.as_ptr(),
+                uchar.as_c_ptr(),
+                uchar.len() as i32,
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        let mut utf16_boundaries = vec![];
+        unsafe {
+            let mut boundary = versioned_function!(ubrk_first)(rep);
+            while boundary != DONE {
+                utf16_boundaries.push(boundary as usize);
+                boundary = versioned_function!(ubrk_next)(rep);
+            }
+            versioned_function!(ubrk_close)(rep);
+        }
+
+        // ICU reports the boundaries as UTF-16 offsets, which are converted to byte offsets by
+        // walking `text` in step with them.
+        let mut boundaries = Vec::with_capacity(utf16_boundaries.len());
+        let mut utf16_offset = 0;
+        let mut chars = text.char_indices().peekable();
+        for utf16_boundary in utf16_boundaries {
+            while utf16_offset < utf16_boundary {
+                let (_, c) = chars.next().expect("boundary is within text");
+                utf16_offset += c.len_utf16();
+            }
+            boundaries.push(chars.peek().map(|(i, _)| *i).unwrap_or(text.len()));
+        }
+        Ok(boundaries)
+    }
+
+    /// Transliterates `text`, written in the likely script of this locale, into `target_script`.
+    ///
+    /// The transliterator used is the ICU system transliterator with the ID
+    /// `<source>-<target_script>`, where `<source>` is the [likely_script](ULoc::likely_script)
+    /// of this locale; for example `Cyrl-Latn` for `ru` and `Latn`.  `target_script` may be any
+    /// spelling of a script that ICU accepts in transliterator IDs, such as `Latn` or `Latin`.
+    /// Fails if this locale has no likely script, or if ICU has no such transliterator.
+    pub fn transliterate(&self, text: &str, target_script: &str) -> Result<String, common::Error> {
+        let source_script = self.likely_script()?.ok_or_else(|| {
+            common::Error::wrapper(anyhow!("locale {:?} has no likely script", self.repr))
+        })?;
+        let id = format!("{}-{}", source_script, target_script);
+        utrans::UTransliterator::new(&id, None, sys::UTransDirection::UTRANS_FORWARD)?
+            .transliterate(text)
+    }
+
+    /// Converts `text` to uppercase, using the casing rules of this locale.
+    ///
+    /// Unlike [str::to_uppercase], this applies language-specific rules, such as uppercasing `i`
+    /// to `İ` in Turkish.
+    ///
+    /// Implements `u_strToUpper` from ICU4C.
+    pub fn to_uppercase(&self, text: &str) -> Result<String, common::Error> {
+        let asciiz_locale = self.as_c_str();
+        case_map(text, |src, dest, status| unsafe {
+            versioned_function!(u_strToUpper)(
+                dest.as_mut_ptr(),
+                dest.len() as i32,
+                src.as_c_ptr(),
+                src.len() as i32,
+                asciiz_locale.as_ptr(),
+                status,
+            )
+        })
+    }
+
+    /// Converts `text` to lowercase, using the casing rules of this locale.
+    ///
+    /// Unlike [str::to_lowercase], this applies language-specific rules, such as lowercasing `I`
+    /// to `ı` in Turkish.
+    ///
+    /// Implements `u_strToLower` from ICU4C.
+    pub fn to_lowercase(&self, text: &str) -> Result<String, common::Error> {
+        let asciiz_locale = self.as_c_str();
+        case_map(text, |src, dest, status| unsafe {
+            versioned_function!(u_strToLower)(
+                dest.as_mut_ptr(),
+                dest.len() as i32,
+                src.as_c_ptr(),
+                src.len() as i32,
+                asciiz_locale.as_ptr(),
+                status,
+            )
+        })
+    }
+
+    /// Converts `text` to title case, using the casing rules and word boundaries of this locale.
+    ///
+    /// The first letter of each word is titlecased and the rest of the word is lowercased, so
+    /// `hello world` becomes `Hello World`.  Language-specific rules apply, such as titlecasing
+    /// the `ij` digraph as a whole in Dutch.
+    ///
+    /// Implements `u_strToTitle` from ICU4C.
+    pub fn to_title_case(&self, text: &str) -> Result<String, common::Error> {
+        let asciiz_locale = self.as_c_str();
+        case_map(text, |src, dest, status| unsafe {
+            versioned_function!(u_strToTitle)(
+                dest.as_mut_ptr(),
+                dest.len() as i32,
+                src.as_c_ptr(),
+                src.len() as i32,
+                // ICU opens a word break iterator for the locale when none is given.
+                ptr::null_mut(),
+                asciiz_locale.as_ptr(),
+                status,
+            )
+        })
+    }
+
+    /// Returns the current label of this locale.
+    pub fn label(&self) -> &str {
+        &self.repr
+    }
+
+    /// Returns whether the ID of this locale consists of ASCII characters only.
+    ///
+    /// Canonicalizing an ASCII ID always yields an ASCII ID, but [ULoc::try_from] does not reject
+    /// non-ASCII input, which ICU passes through to the ID.  Use this to check IDs from untrusted
+    /// sources.
+    pub fn is_ascii_id(&self) -> bool {
+        self.repr.is_ascii()
+    }
+
+    /// Returns the label of this locale with the subtags of its base name separated by dashes,
+    /// such as `en-US@calendar=japanese` for `en_US@calendar=japanese`.
+    ///
+    /// This is a cosmetic transform, not a conversion to BCP-47: keywords are left as they are,
+    /// and the result is not necessarily a valid language tag.  Use
+    /// [to_language_tag](ULoc::to_language_tag) for that.
+    pub fn id_with_dashes(&self) -> String {
+        match self.repr.find('@') {
+            Some(at) => {
+                let (base_name, keywords) = self.repr.split_at(at);
+                base_name.replace('_', "-") + keywords
+            }
+            None => self.repr.replace('_', "-"),
+        }
+    }
+
+    /// Returns the bytes of the current label of this locale, without a terminating NUL.
+    ///
+    /// Unlike [as_c_str](ULoc::as_c_str), this does not allocate; the caller is responsible for
+    /// NUL-termination if the bytes are handed to C code.
+    pub fn repr_bytes(&self) -> &[u8] {
+        self.repr.as_bytes()
+    }
+
+    /// Returns true if `c` holds exactly the ID of this locale.
+    ///
+    /// This compares bytes, without allocating or canonicalizing `c`, so it is only meaningful
+    /// for IDs that are already canonical, such as those returned by ICU.  For example, `en_US`
+    /// matches the locale `en-US`, but `en-US` does not.
+    pub fn eq_c_str(&self, c: &ffi::CStr) -> bool {
+        c.to_bytes() == self.repr.as_bytes()
+    }
+
+    /// Returns a 64-bit fingerprint of this locale, which is the same in every process and on
+    /// every platform, unlike the [Hash] of a locale.
+    ///
+    /// This is the 64-bit FNV-1a hash of the [label](ULoc::label), so equal locales have equal
+    /// fingerprints.  It is not a cryptographic hash.
+    pub fn fingerprint(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+        self.repr.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        })
+    }
+
+    /// Returns the current locale name as a C string.
+    pub fn as_c_str(&self) -> ffi::CString {
+        ffi::CString::new(self.repr.clone()).expect("ULoc contained interior NUL bytes")
+    }
+
+    /// Implements `uloc_forLanguageTag` from ICU4C.
+    ///
+    /// Like `icu::Locale::forLanguageTag()` from the C++ API, a tag that ICU can not parse in its
+    /// entirety is rejected with `U_ILLEGAL_ARGUMENT_ERROR`, which can be told apart from other
+    /// failures by checking [common::Error::error_code].
+    ///
+    /// The grandfathered tags of BCP-47 are mapped to their preferred values, such as `tlh` for
+    /// `i-klingon` and `nan` for `zh-min-nan`.  The few with no preferred value, such as
+    /// `i-default`, are kept as a private use extension, as in `en@x=i-default`.
+    pub fn for_language_tag(tag: &str) -> Result<ULoc, common::Error> {
+        buffered_string_method_with_retry!(
+            buffered_string_for_language_tag,
+            LOCALE_CAPACITY,
+            [tag: *const raw::c_char,],
+            [parsed_length: *mut i32,]
+        );
+
+        let tag = str_to_cstring(tag);
+        let mut parsed_length: i32 = 0;
+        let locale_id = buffered_string_for_language_tag(
+            versioned_function!(uloc_forLanguageTag),
+            tag.as_ptr(),
+            &mut parsed_length,
+        )?;
+        if parsed_length as usize != tag.as_bytes().len() {
+            return Err(common::Error::Sys(UErrorCode::U_ILLEGAL_ARGUMENT_ERROR));
+        }
+        ULoc::try_from(&locale_id[..])
+    }
+
+    /// Creates a `ULoc` from a POSIX locale ID, as found in the `LANG` and `LC_*` environment
+    /// variables.
+    ///
+    /// A POSIX locale ID has the form `language[_territory][.codeset][@modifier]`.  The
+    /// conversion follows the one ICU applies when it determines its own default locale:
+    ///
+    /// * the codeset is dropped, so `en_US.UTF-8` becomes `en_US`;
+    /// * the special locales `C` and `POSIX` become `en_US_POSIX`;
+    /// * the modifier becomes the variant, so `ca_ES@valencia` becomes `ca_ES_VALENCIA`; the
+    ///   modifier `nynorsk` is mapped to the variant `NY`.
+    pub fn from_posix(posix_id: &str) -> Result<ULoc, common::Error> {
+        let (base, modifier) = match posix_id.find('@') {
+            Some(index) => (&posix_id[..index], Some(&posix_id[index + 1..])),
+            None => (posix_id, None),
+        };
+        let base = base.split('.').next().unwrap_or_default();
+        if base == "C" || base == "POSIX" {
+            return ULoc::try_from("en_US_POSIX");
+        }
+
+        let mut id = base.to_string();
+        if let Some(modifier) = modifier {
+            // The modifier may itself be followed by a codeset.
+            let modifier = match modifier.split('.').next().unwrap_or_default() {
+                "nynorsk" => "NY",
+                modifier => modifier,
+            };
+            if !modifier.is_empty() {
+                id.push_str(if id.contains('_') { "_" } else { "__" });
+                id.push_str(modifier);
+            }
+        }
+        ULoc::try_from(&id[..])
+    }
+
+    /// Creates a `ULoc` from a Windows locale name, such as `en-US` or `de-DE_phoneb`.
+    ///
+    /// Windows locale names are BCP-47 language tags, with these exceptions:
+    ///
+    /// * the legacy names `zh-CHS` and `zh-CHT` stand for `zh-Hans` and `zh-Hant`;
+    /// * a sort order may follow the name after an underscore; it becomes the `collation`
+    ///   keyword, so `de-DE_phoneb` becomes `de_DE@collation=phonebook`, `es-ES_tradnl` becomes
+    ///   `es_ES@collation=traditional`, and `zh-TW_pronun`, `zh-TW_radstr` and `zh-CN_stroke`
+    ///   become the `zhuyin`, `unihan` and `stroke` collations.  Other sort orders are rejected.
+    pub fn from_windows_locale_name(name: &str) -> Result<ULoc, common::Error> {
+        let (name, sort_order) = match name.find('_') {
+            Some(index) => (&name[..index], Some(&name[index + 1..])),
+            None => (name, None),
+        };
+        let tag = WINDOWS_LOCALE_NAMES
+            .iter()
+            .find(|(windows_name, _)| windows_name.eq_ignore_ascii_case(name))
+            .map_or(name, |(_, tag)| tag);
+        let loc = ULoc::for_language_tag(tag)?;
+        match sort_order {
+            None => Ok(loc),
+            Some(sort_order) => {
+                let collation = WINDOWS_SORT_ORDERS
+                    .iter()
+                    .find(|(windows_sort_order, _)| {
+                        windows_sort_order.eq_ignore_ascii_case(sort_order)
+                    })
+                    .map(|(_, collation)| *collation)
+                    .ok_or_else(|| {
+                        common::Error::wrapper(anyhow!(
+                            "unsupported Windows sort order {:?}",
+                            sort_order
+                        ))
+                    })?;
+                loc.set_keyword_value("collation", Some(collation))
+            }
+        }
+    }
+
+    /// Like [for_language_tag](ULoc::for_language_tag), but tolerates common deviations from
+    /// BCP-47 syntax found in user input.
+    ///
+    /// Before parsing, leading and trailing whitespace is trimmed, and underscores are replaced
+    /// with hyphens, so that both `en_us` and ` en-US ` are accepted.  BCP-47 tags are case
+    /// insensitive, so no case normalization is needed.
+    pub fn for_language_tag_lenient(tag: &str) -> Result<ULoc, common::Error> {
+        ULoc::for_language_tag(&tag.trim().replace('_', "-"))
+    }
+
+    /// Like [for_language_tag](ULoc::for_language_tag), but accepts only hyphenated BCP-47 tags.
+    ///
+    /// ICU's own locale ID syntax, such as `en_US@calendar=japanese`, is rejected up front with
+    /// an error naming the offending character, rather than being partially parsed.
+    pub fn parse_bcp47_strict(tag: &str) -> Result<ULoc, common::Error> {
+        if let Some(c) = tag.chars().find(|c| *c == '@' || *c == '_') {
+            return Err(common::Error::wrapper(anyhow!(
+                "not a BCP-47 language tag: {:?} contains {:?}",
+                tag,
+                c
+            )));
+        }
+        ULoc::for_language_tag(tag)
+    }
+
+    /// Like `ULoc::try_from`, but rejects `tag` up front if it is longer than `max_len` bytes.
+    ///
+    /// Use this for untrusted input, such as the language ranges of a hostile `Accept-Language`
+    /// header: an overlong input is turned down before it is handed to ICU, so it can not make
+    /// ICU allocate buffers to fit it.
+    pub fn try_from_bounded(tag: &str, max_len: usize) -> Result<ULoc, common::Error> {
+        if tag.len() > max_len {
+            return Err(common::Error::wrapper(anyhow!(
+                "locale is {} bytes long, longer than the limit of {} bytes",
+                tag.len(),
+                max_len
+            )));
+        }
+        ULoc::try_from(tag)
+    }
+
+    /// Like `ULoc::try_from`, but also reports whether the canonical locale ID differs from
+    /// `input`, for example to warn about a tag that was not typed in canonical form.
+    ///
+    /// Leading and trailing whitespace, which `ULoc::try_from` removes, does not count as a
+    /// difference.  So `en-US` is reported as differing from its canonical form `en_US`, while
+    /// `en_US` and ` en_US ` are not.
+    pub fn canonicalize_reporting(input: &str) -> Result<(ULoc, bool), common::Error> {
+        let input = trim_locale_id(input);
+        let loc = ULoc::try_from(input)?;
+        let differs = loc.repr != input;
+        Ok((loc, differs))
+    }
+
+    /// Like `ULoc::try_from`, but also requires ICU to have data for the locale, or for one of
+    /// its ancestors other than the root locale.
+    ///
+    /// The ancestors are those of [fallback_iter](ULoc::fallback_iter), and are looked up in
+    /// [available_locales], ignoring keywords; so `en_US@calendar=japanese` and `de_CH_1901` are
+    /// accepted, but `xx_YY`, which canonicalizes fine, is rejected.
+    pub fn try_from_available(tag: &str) -> Result<ULoc, common::Error> {
+        let loc = ULoc::try_from(tag)?;
+        let available = available_locales();
+        let has_data = loc
+            .base_name()?
+            .fallback_iter()
+            .take_while(|ancestor| !ancestor.repr.is_empty())
+            .any(|ancestor| available.contains(&ancestor));
+        if !has_data {
+            return Err(common::Error::wrapper(anyhow!(
+                "no locale data for {:?}",
+                loc.repr
+            )));
+        }
+        Ok(loc)
+    }
+
+    /// Call a `uloc` method that takes this locale's ID and returns a string.
+    fn call_buffered_string_method(
+        &self,
+        uloc_method: unsafe extern "C" fn(
+            *const raw::c_char,
+            *mut raw::c_char,
+            i32,
+            *mut UErrorCode,
+        ) -> i32,
+    ) -> Result<String, common::Error> {
+        buffered_string_method_with_retry!(
+            buffered_string_char_star,
+            LOCALE_CAPACITY,
+            [char_star: *const raw::c_char,],
+            []
+        );
+        let asciiz = self.as_c_str();
+        buffered_string_char_star(uloc_method, asciiz.as_ptr())
+    }
+
+    /// Call a `uloc` method that takes this locale's ID and a display locale's ID, and returns
+    /// a `UChar` string.
+    fn call_buffered_display_method(
+        &self,
+        uloc_method: unsafe extern "C" fn(
+            *const raw::c_char,
+            *const raw::c_char,
+            *mut sys::UChar,
+            i32,
+            *mut UErrorCode,
+        ) -> i32,
+        display_locale: &ULoc,
+    ) -> Result<String, common::Error> {
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_display_method,
+            LOCALE_CAPACITY,
+            [
+                locale_id: *const raw::c_char,
+                display_locale_id: *const raw::c_char,
+            ],
+            []
+        );
+        let locale_id = self.as_c_str();
+        let display_locale_id = display_locale.as_c_str();
+        let uchar = buffered_uchar_display_method(
+            uloc_method,
+            locale_id.as_ptr(),
+            display_locale_id.as_ptr(),
+        )?;
+        String::try_from(&uchar)
+    }
+
+    /// Implements `uregion_getRegionFromCode` from ICU4C, for the region of this locale.
+    ///
+    /// The returned region is owned by ICU, and lives for as long as the ICU library is loaded.
+    fn uregion(&self) -> Result<*const sys::URegion, common::Error> {
+        let region_code = str_to_cstring(&self.country().unwrap_or_default());
+        let mut status = common::Error::OK_CODE;
+        let region = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(uregion_getRegionFromCode)(region_code.as_ptr(), &mut status)
+        };
+        common::Error::ok_or_warning(status)?;
+        Ok(region)
+    }
+
+    /// Rebuilds this locale's ID from its parts, with the variant replaced by `variant`.
+    fn with_replaced_variant(&self, variant: Option<&str>) -> Result<ULoc, common::Error> {
+        let mut id = self.language().unwrap_or_default();
+        if let Some(script) = self.script() {
+            id.push('_');
+            id.push_str(&script);
+        }
+        let country = self.country();
+        if country.is_some() || variant.is_some() {
+            id.push('_');
+            id.push_str(&country.unwrap_or_default());
+        }
+        if let Some(variant) = variant {
+            id.push('_');
+            id.push_str(variant);
+        }
+        if let Some(keywords_start) = self.repr.find('@') {
+            id.push_str(&self.repr[keywords_start..]);
+        }
+        ULoc::try_from(id.as_str())
+    }
+
+    /// Returns the parts of this locale for [fmt::Debug], without panicking.
+    fn debug_parts(&self) -> Result<Vec<(&'static str, String)>, common::Error> {
+        Ok(vec![
+            (
+                "language",
+                self.call_buffered_string_method(versioned_function!(uloc_getLanguage))?,
+            ),
+            (
+                "script",
+                self.call_buffered_string_method(versioned_function!(uloc_getScript))?,
+            ),
+            (
+                "country",
+                self.call_buffered_string_method(versioned_function!(uloc_getCountry))?,
+            ),
+            (
+                "variant",
+                self.call_buffered_string_method(versioned_function!(uloc_getVariant))?,
+            ),
+        ])
+    }
+
+    /// Returns the keywords of this locale for [fmt::Debug], without panicking.
+    fn debug_keywords(&self) -> Result<BTreeMap<String, String>, common::Error> {
+        let mut keywords = BTreeMap::new();
+        for keyword in rust_icu_uenum::uloc_open_keywords(&self.repr)? {
+            let keyword = keyword?;
+            if let Some(value) = self.keyword_value(&keyword)? {
+                keywords.insert(keyword, value);
+            }
+        }
+        Ok(keywords)
+    }
+
+    /// Call a `uloc` method that takes this locale's ID, panics on any errors, and returns
+    /// `Some(result)` if the resulting string is non-empty, or `None` otherwise.
+    fn call_buffered_string_method_to_option(
+        &self,
+        uloc_method: unsafe extern "C" fn(
+            *const raw::c_char,
+            *mut raw::c_char,
+            i32,
+            *mut UErrorCode,
+        ) -> i32,
+    ) -> Option<String> {
+        let value: String = self.call_buffered_string_method(uloc_method).unwrap();
+        if value.is_empty() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+/// This implementation is based on ULocale.compareTo from ICU4J.
+/// See https://github.com/unicode-org/icu/blob/master/icu4j/main/classes/core/src/com/ibm/icu/util/ULocale.java
+impl Ord for ULoc {
+    fn cmp(&self, other: &Self) -> Ordering {
+        /// Compare corresponding keywords from two `ULoc`s. If the keywords match, compare the
+        /// keyword values.
+        fn compare_keywords(
+            this: &ULoc,
+            self_keyword: &Option<String>,
+            other: &ULoc,
+            other_keyword: &Option<String>,
+        ) -> Option<Ordering> {
+            match (self_keyword, other_keyword) {
+                (Some(self_keyword), Some(other_keyword)) => {
+                    // Compare the two keywords
+                    match self_keyword.cmp(&other_keyword) {
+                        Ordering::Equal => {
+                            // Compare the two keyword values
+                            let self_val = this.keyword_value(&self_keyword[..]).unwrap();
+                            let other_val = other.keyword_value(&other_keyword[..]).unwrap();
+                            Some(self_val.cmp(&other_val))
+                        }
+                        unequal_ordering => Some(unequal_ordering),
+                    }
+                }
+                // `other` has run out of keywords
+                (Some(_), _) => Some(Ordering::Greater),
+                // `this` has run out of keywords
+                (_, Some(_)) => Some(Ordering::Less),
+                // Both iterators have run out
+                (_, _) => None,
+            }
+        }
+
+        self.language()
+            .cmp(&other.language())
+            .then_with(|| self.script().cmp(&other.script()))
+            .then_with(|| self.country().cmp(&other.country()))
+            .then_with(|| self.variant().cmp(&other.variant()))
+            .then_with(|| {
+                let mut self_keywords = self.keywords();
+                let mut other_keywords = other.keywords();
+
+                while let Some(keyword_ordering) =
+                    compare_keywords(self, &self_keywords.next(), other, &other_keywords.next())
+                {
+                    match keyword_ordering {
+                        Ordering::Equal => {}
+                        unequal_ordering => {
+                            return unequal_ordering;
+                        }
+                    }
+                }
+
+                // All keywords and values were identical (or there were none)
+                Ordering::Equal
+            })
+    }
+}
+
+impl PartialOrd for ULoc {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Gets the current system default locale.
+///
+/// Implements `uloc_getDefault` from ICU4C.
+pub fn get_default() -> ULoc {
+    let loc = unsafe { versioned_function!(uloc_getDefault)() };
+    let uloc_cstr = unsafe { ffi::CStr::from_ptr(loc) };
+    crate::ULoc::try_from(uloc_cstr).expect("could not convert default locale to ULoc")
+}
+
+/// Sets the current default system locale.
+///
+/// Implements `uloc_setDefault` from ICU4C.
+pub fn set_default(loc: &ULoc) -> Result<(), common::Error> {
+    let mut status = common::Error::OK_CODE;
+    let asciiz = str_to_cstring(&loc.repr);
+    unsafe { versioned_function!(uloc_setDefault)(asciiz.as_ptr(), &mut status) };
+    common::Error::ok_or_warning(status)
+}
+
+/// Sets the current default system locale from the POSIX locale environment variables.
+///
+/// The variables are consulted in POSIX precedence order: `LC_ALL`, then `LC_MESSAGES`, then
+/// `LANG`.  The first variable that is set to a non-empty value is converted using
+/// [ULoc::from_posix].  If none of them is set, the default locale is set to the root locale.
+///
+/// Returns the locale that was set as the default.
+pub fn set_default_from_environment() -> Result<ULoc, common::Error> {
+    let posix_id = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty());
+    let loc = match posix_id {
+        Some(posix_id) => ULoc::from_posix(&posix_id)?,
+        None => ULoc::try_from("")?,
+    };
+    set_default(&loc)?;
+    Ok(loc)
+}
+
+/// Returns all locales for which ICU has data.
+///
+/// Implements `uloc_countAvailable` and `uloc_getAvailable` from ICU4C.
+pub fn available_locales() -> Vec<ULoc> {
+    let count = unsafe { versioned_function!(uloc_countAvailable)() };
+    (0..count)
+        .map(|index| {
+            let loc = unsafe { versioned_function!(uloc_getAvailable)(index) };
+            let uloc_cstr = unsafe { ffi::CStr::from_ptr(loc) };
+            ULoc::try_from(uloc_cstr).expect("could not convert available locale to ULoc")
+        })
+        .collect()
+}
+
+/// Returns the available locales whose language subtag is `lang`.
+///
+/// The language subtags are compared for equality, so `"en"` yields `en`, `en_US`, `en_GB` and
+/// so on, but does not yield `eo` or `enn`.
+pub fn available_locales_with_language(lang: &str) -> impl Iterator<Item = ULoc> {
+    let lang = lang.to_string();
+    available_locales()
+        .into_iter()
+        .filter(move |uloc| uloc.language().as_deref() == Some(lang.as_str()))
+}
+
+/// Parses the value of an HTTP `Accept-Language` header into locales and their quality values.
+///
+/// The result is sorted by descending quality; entries of equal quality keep their order in the
+/// header.  A missing or malformed `q` parameter counts as `1.0`, and out-of-range values are
+/// clamped to `[0.0, 1.0]`.  The wildcard range `*` becomes the root locale.  Fails if a language
+/// range is not a well-formed BCP-47 tag.
+pub fn parse_accept_language(header: &str) -> Result<Vec<(ULoc, f32)>, common::Error> {
+    let mut ranges = vec![];
+    for entry in header.split(',') {
+        let mut parts = entry.split(';').map(str::trim);
+        let range = parts.next().unwrap_or_default();
+        if range.is_empty() {
+            continue;
+        }
+        let quality = parts
+            .filter_map(|param| {
+                let (name, value) = param.split_at(param.find('=')?);
+                if name.trim().eq_ignore_ascii_case("q") {
+                    Some(value[1..].trim().parse::<f32>().unwrap_or(1.0))
+                } else {
+                    None
+                }
+            })
+            .next()
+            .filter(|quality| !quality.is_nan())
+            .unwrap_or(1.0)
+            .clamp(0.0, 1.0);
+        let locale = if range == "*" {
+            ULoc::try_from("")?
+        } else {
+            ULoc::for_language_tag(range)?
+        };
+        ranges.push((locale, quality));
+    }
+    ranges.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+    Ok(ranges)
+}
+
+/// Implements `uloc_acceptLanguage` from ICU4C.
+pub fn accept_language(
+    accept_list: impl IntoIterator<Item = impl IntoLocale>,
+    available_locales: impl IntoIterator<Item = impl IntoLocale>,
+) -> Result<(Option<ULoc>, UAcceptResult), common::Error> {
+    buffered_string_method_with_retry!(
+        buffered_string_uloc_accept_language,
+        LOCALE_CAPACITY,
+        [],
+        [
+            out_result: *mut UAcceptResult,
+            accept_list: *mut *const ::std::os::raw::c_char,
+            accept_list_count: i32,
+            available_locales: *mut UEnumeration,
+        ]
+    );
+
+    let mut accept_result: UAcceptResult = UAcceptResult::ULOC_ACCEPT_FAILED;
+    let mut accept_list_cstrings: Vec<ffi::CString> = vec![];
+    // This is mutable only to satisfy the missing `const`s in the ICU4C API.
+    let mut accept_list: Vec<*const raw::c_char> = accept_list
+        .into_iter()
+        .map(|item| {
+            let uloc = item.into_locale();
+            accept_list_cstrings.push(uloc.as_c_str());
+            accept_list_cstrings
+                .last()
+                .expect("non-empty list")
+                .as_ptr()
+        })
+        .collect();
+
+    let available_locales: Vec<ULoc> = available_locales
+        .into_iter()
+        .map(IntoLocale::into_locale)
         .collect();
     let available_locales: Vec<&str> = available_locales.iter().map(|uloc| uloc.label()).collect();
     let mut available_locales = Enumeration::try_from(&available_locales[..])?;
 
-    let matched_locale = buffered_string_uloc_accept_language(
-        versioned_function!(uloc_acceptLanguage),
-        &mut accept_result,
-        accept_list.as_mut_ptr(),
-        accept_list.len() as i32,
-        available_locales.repr(),
-    );
+    let matched_locale = buffered_string_uloc_accept_language(
+        versioned_function!(uloc_acceptLanguage),
+        &mut accept_result,
+        accept_list.as_mut_ptr(),
+        accept_list.len() as i32,
+        available_locales.repr(),
+    );
+
+    // Having no match is a valid if disappointing result.
+    if accept_result == UAcceptResult::ULOC_ACCEPT_FAILED {
+        return Ok((None, accept_result));
+    }
+
+    matched_locale
+        .and_then(|s| ULoc::try_from(s.as_str()))
+        .map(|uloc| (Some(uloc), accept_result))
+}
+
+/// Finds the locale from `supported` that best matches the locales in `desired`.
+///
+/// The negotiation happens in two stages:
+///
+/// 1. The locales are matched as given, using [accept_language].  This finds exact matches,
+///    and fallback matches where a `supported` locale is an ancestor of a `desired` one.
+/// 2. If the first stage finds no match, both lists are maximized using
+///    [add_likely_subtags](ULoc::add_likely_subtags), and matched again.  This catches matches
+///    that are implied by the likely script or region, e.g. desired `und_Latn_RS` against
+///    supported `sr_Latn`, which both maximize to `sr_Latn_RS`.  Before ICU 67, this is also
+///    where desired `zh_TW` matches supported `zh_Hant`, which both maximize to `zh_Hant_TW`;
+///    since ICU 67, [accept_language] finds that match in the first stage already.
+///
+/// The returned locale is always an element of `supported`, as passed in.  Returns `None` if
+/// neither stage finds a match, or if ICU reports an error.
+pub fn negotiate(desired: &[ULoc], supported: &[ULoc]) -> Option<ULoc> {
+    match accept_language(desired, supported) {
+        Ok((Some(uloc), _)) => return Some(uloc),
+        Ok((None, _)) => {}
+        Err(_) => return None,
+    }
+
+    let maximize = |locales: &[ULoc]| -> Result<Vec<ULoc>, common::Error> {
+        locales.iter().map(ULoc::add_likely_subtags).collect()
+    };
+    let desired_max = maximize(desired).ok()?;
+    let supported_max = maximize(supported).ok()?;
+    let (matched, _) = accept_language(desired_max, &supported_max).ok()?;
+
+    // Map the maximized match back to the locale the caller supplied.
+    let matched = matched?;
+    supported_max
+        .iter()
+        .position(|uloc| *uloc == matched)
+        .map(|index| supported[index].clone())
+}
+
+/// Applies [ULoc::add_likely_subtags] to each of `locales`.
+///
+/// Fails on the first locale that can not be maximized; the error names its index in `locales`.
+pub fn maximize_all(locales: &[ULoc]) -> Result<Vec<ULoc>, common::Error> {
+    map_all(locales, ULoc::add_likely_subtags)
+}
+
+/// Applies [ULoc::minimize_subtags] to each of `locales`.
+///
+/// Fails on the first locale that can not be minimized; the error names its index in `locales`.
+pub fn minimize_all(locales: &[ULoc]) -> Result<Vec<ULoc>, common::Error> {
+    map_all(locales, ULoc::minimize_subtags)
+}
+
+/// Applies `f` to each of `locales`, adding the index of the failing locale to any error.
+fn map_all(
+    locales: &[ULoc],
+    f: impl Fn(&ULoc) -> Result<ULoc, common::Error>,
+) -> Result<Vec<ULoc>, common::Error> {
+    locales
+        .iter()
+        .enumerate()
+        .map(|(index, locale)| {
+            f(locale).map_err(|e| {
+                common::Error::wrapper(
+                    anyhow::Error::new(e)
+                        .context(format!("at index {}, locale {:?}", index, locale.repr)),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Returns true if the tags `a` and `b` name the same locale once canonicalized.
+///
+/// Tags that can not be canonicalized are never equal to anything, including themselves.
+pub fn tags_equal(a: &str, b: &str) -> bool {
+    match (ULoc::try_from(a), ULoc::try_from(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Returns true if `tag` is a BCP-47 language tag in canonical form.
+///
+/// A tag is canonical here if parsing it with [ULoc::for_language_tag] and serializing the
+/// result with [ULoc::to_language_tag] in strict mode reproduces `tag` exactly.  Among other
+/// things, this requires the canonical casing of each subtag, so `en-US` is canonical while
+/// `en-us` is not, and that deprecated subtags are replaced by their preferred values.
+pub fn is_canonical_bcp47(tag: &str) -> bool {
+    ULoc::for_language_tag(tag)
+        .and_then(|locale| locale.to_language_tag(true))
+        .map(|canonical| canonical == tag)
+        .unwrap_or(false)
+}
+
+/// Sorts `locales` in place by their names as displayed in `display_locale`.
+///
+/// This is the order a language picker for users of `display_locale` would use.  See
+/// [ULoc::cmp_by_display_name] for comparing a single pair of locales; this function looks up
+/// each display name and opens the collator only once.
+pub fn sort_by_display_name(
+    locales: &mut [ULoc],
+    display_locale: &ULoc,
+) -> Result<(), common::Error> {
+    let collator = ucol::UCollator::try_from(display_locale.label())?;
+    let mut named = locales
+        .iter()
+        .map(|locale| Ok((locale.display_name(display_locale)?, locale.clone())))
+        .collect::<Result<Vec<(String, ULoc)>, common::Error>>()?;
+    let mut error = None;
+    named.sort_by(|(first, _), (second, _)| {
+        collator.strcoll_utf8(first, second).unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            Ordering::Equal
+        })
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+    for (slot, (_, locale)) in locales.iter_mut().zip(named) {
+        *slot = locale;
+    }
+    Ok(())
+}
+
+/// Returns the canonical spelling of the keyword name `key`, whichever way it is spelled.
+///
+/// The canonical spelling is the lowercase name ICU uses in locale IDs, so that `calendar`,
+/// `Calendar`, and the BCP-47 key `ca` all become `calendar`.  This round-trips `key` through
+/// [to_unicode_locale_key] and [to_legacy_key]; well-formed keys that ICU does not know of, such
+/// as `foo`, are returned in lowercase.  Fails if `key` is not a well-formed keyword name.
+pub fn canonical_keyword(key: &str) -> Result<String, common::Error> {
+    let lowercase = key.to_ascii_lowercase();
+    to_unicode_locale_key(&lowercase)
+        .and_then(|unicode_key| to_legacy_key(&unicode_key))
+        .or_else(|| to_legacy_key(&lowercase))
+        .filter(|canonical| !canonical.is_empty())
+        .ok_or_else(|| common::Error::wrapper(anyhow!("not a valid keyword name: {:?}", key)))
+}
+
+/// Returns all values ICU recognizes for the keyword `keyword`, in their legacy spelling.
+///
+/// The keyword may be spelled in any way accepted by [canonical_keyword].  Only the `calendar`
+/// and `collation` keywords are supported; for any other keyword this fails with
+/// `U_UNSUPPORTED_ERROR`.
+///
+/// Implements `ucal_getKeywordValuesForLocale` and `ucol_getKeywordValues` from ICU4C.
+pub fn keyword_values(keyword: &str) -> Result<Enumeration, common::Error> {
+    let keyword = canonical_keyword(keyword)?;
+    let asciiz_keyword = str_to_cstring(&keyword);
+    let mut status = common::Error::OK_CODE;
+    let raw_enum = unsafe {
+        assert!(common::Error::is_ok(status));
+        match keyword.as_str() {
+            "calendar" => versioned_function!(ucal_getKeywordValuesForLocale)(
+                asciiz_keyword.as_ptr(),
+                // The root locale, with all values rather than only the commonly used ones.
+                b"\0".as_ptr() as *const raw::c_char,
+                false as sys::UBool,
+                &mut status,
+            ),
+            "collation" => {
+                versioned_function!(ucol_getKeywordValues)(asciiz_keyword.as_ptr(), &mut status)
+            }
+            _ => return Err(common::Error::Sys(sys::UErrorCode::U_UNSUPPORTED_ERROR)),
+        }
+    };
+    common::Error::ok_or_warning(status)?;
+    if raw_enum.is_null() {
+        return Ok(Enumeration::empty());
+    }
+    Ok(unsafe { Enumeration::from_raw_parts(None, raw_enum) })
+}
+
+/// Returns whether `value` is a value ICU recognizes for the keyword `keyword`.
+///
+/// Both the legacy and the BCP-47 spellings of the value are accepted, so `("calendar",
+/// "gregorian")` and `("ca", "gregory")` are both valid.  Returns `false` for keywords not
+/// supported by [keyword_values].
+pub fn is_valid_keyword_value(keyword: &str, value: &str) -> bool {
+    let keyword = match canonical_keyword(keyword) {
+        Ok(keyword) => keyword,
+        Err(_) => return false,
+    };
+    let value = value.to_ascii_lowercase();
+    let value = to_legacy_type(&keyword, &value).unwrap_or(value);
+    match keyword_values(&keyword) {
+        Ok(values) => values.filter_map(Result::ok).any(|known| known == value),
+        Err(_) => false,
+    }
+}
+
+/// Implements `uloc_toUnicodeLocaleKey` from ICU4C.
+pub fn to_unicode_locale_key(legacy_keyword: &str) -> Option<String> {
+    let legacy_keyword = str_to_cstring(legacy_keyword);
+    let unicode_keyword: Option<ffi::CString> = unsafe {
+        let ptr = versioned_function!(uloc_toUnicodeLocaleKey)(legacy_keyword.as_ptr());
+        ptr.as_ref().map(|ptr| ffi::CStr::from_ptr(ptr).to_owned())
+    };
+    unicode_keyword.map(|cstring| cstring_to_string(&cstring))
+}
+
+/// Implements `uloc_toUnicodeLocaleType` from ICU4C.
+pub fn to_unicode_locale_type(legacy_keyword: &str, legacy_value: &str) -> Option<String> {
+    let legacy_keyword = str_to_cstring(legacy_keyword);
+    let legacy_value = str_to_cstring(legacy_value);
+    let unicode_value: Option<ffi::CString> = unsafe {
+        let ptr = versioned_function!(uloc_toUnicodeLocaleType)(
+            legacy_keyword.as_ptr(),
+            legacy_value.as_ptr(),
+        );
+        ptr.as_ref().map(|ptr| ffi::CStr::from_ptr(ptr).to_owned())
+    };
+    unicode_value.map(|cstring| cstring_to_string(&cstring))
+}
+
+/// Implements `uloc_toLegacyKey` from ICU4C.
+pub fn to_legacy_key(unicode_keyword: &str) -> Option<String> {
+    let unicode_keyword = str_to_cstring(unicode_keyword);
+    let legacy_keyword: Option<ffi::CString> = unsafe {
+        let ptr = versioned_function!(uloc_toLegacyKey)(unicode_keyword.as_ptr());
+        ptr.as_ref().map(|ptr| ffi::CStr::from_ptr(ptr).to_owned())
+    };
+    legacy_keyword.map(|cstring| cstring_to_string(&cstring))
+}
+
+/// Implements `uloc_toLegacyType` from ICU4C.
+pub fn to_legacy_type(unicode_keyword: &str, unicode_value: &str) -> Option<String> {
+    let unicode_keyword = str_to_cstring(unicode_keyword);
+    let unicode_value = str_to_cstring(unicode_value);
+    let legacy_value: Option<ffi::CString> = unsafe {
+        let ptr = versioned_function!(uloc_toLegacyType)(
+            unicode_keyword.as_ptr(),
+            unicode_value.as_ptr(),
+        );
+        ptr.as_ref().map(|ptr| ffi::CStr::from_ptr(ptr).to_owned())
+    };
+    legacy_value.map(|cstring| cstring_to_string(&cstring))
+}
+
+/// Renders display names of locales and their parts, as seen from a single display locale.
+///
+/// Unlike [ULoc::display_name], which looks up the display locale's data on each call, this
+/// keeps the data open for as long as the handle lives, which is cheaper when rendering many
+/// names.  It also renders names of bare codes, such as `Cyrl` or `JP`, without needing to build
+/// a full locale around them.
+///
+/// The way [locale_display_name](LocaleDisplayNames::locale_display_name) joins the parts of a
+/// locale's name can be overridden with [with_separator](LocaleDisplayNames::with_separator) and
+/// [with_pattern](LocaleDisplayNames::with_pattern).
+#[derive(Debug)]
+pub struct LocaleDisplayNames {
+    rep: ptr::NonNull<sys::ULocaleDisplayNames>,
+    // Overrides for the CLDR patterns of the display locale; see `with_separator`.
+    separator: Option<String>,
+    pattern: Option<String>,
+}
+
+impl Drop for LocaleDisplayNames {
+    /// Implements `uldn_close` from ICU4C.
+    fn drop(&mut self) {
+        unsafe { versioned_function!(uldn_close)(self.rep.as_ptr()) };
+    }
+}
+
+impl LocaleDisplayNames {
+    /// Implements `uldn_open` from ICU4C.
+    pub fn try_new(
+        display_locale: &ULoc,
+        dialect_handling: sys::UDialectHandling,
+    ) -> Result<LocaleDisplayNames, common::Error> {
+        let display_locale_id = display_locale.as_c_str();
+        let mut status = common::Error::OK_CODE;
+        // Unsafety note: uldn_open copies the locale ID, and returns null on error.
+        let rep = unsafe {
+            assert!(common::Error::is_ok(status));
+            versioned_function!(uldn_open)(
+                display_locale_id.as_ptr(),
+                dialect_handling,
+                &mut status,
+            )
+        };
+        common::Error::ok_or_warning(status)?;
+        Ok(LocaleDisplayNames {
+            rep: ptr::NonNull::new(rep).unwrap(),
+            separator: None,
+            pattern: None,
+        })
+    }
+
+    /// Sets the separator placed between the qualifiers of a locale's name, such as its script
+    /// and region, in place of the display locale's own, which is `, ` in English.
+    ///
+    /// This intentionally bypasses the CLDR patterns of the display locale: once a separator or
+    /// a [pattern](LocaleDisplayNames::with_pattern) is set,
+    /// [locale_display_name](LocaleDisplayNames::locale_display_name) assembles the name from
+    /// its parts in Rust, using the CLDR defaults for whichever of the two is not set.
+    pub fn with_separator(mut self, separator: &str) -> Self {
+        self.separator = Some(separator.to_string());
+        self
+    }
+
+    /// Sets the pattern combining the language name of a locale, `{0}`, with its qualifiers,
+    /// `{1}`, in place of the display locale's own, which is `{0} ({1})` in English.
+    ///
+    /// See [with_separator](LocaleDisplayNames::with_separator) for how this interacts with the
+    /// CLDR patterns.
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(pattern.to_string());
+        self
+    }
+
+    /// Implements `uldn_localeDisplayName` from ICU4C.
+    ///
+    /// If a separator or pattern override is set, the name is instead assembled from the names
+    /// of the language, script, region, variant and keywords of `locale`.
+    pub fn locale_display_name(&self, locale: &ULoc) -> Result<String, common::Error> {
+        if self.separator.is_none() && self.pattern.is_none() {
+            return self.call_buffered_code_method(
+                versioned_function!(uldn_localeDisplayName),
+                locale.label(),
+            );
+        }
+        let mut qualifiers = vec![];
+        if let Some(script) = locale.script() {
+            qualifiers.push(self.script_name(&script)?);
+        }
+        if let Some(region) = locale.country() {
+            qualifiers.push(self.region_name(&region)?);
+        }
+        if let Some(variant) = locale.variant() {
+            qualifiers.push(self.variant_name(&variant)?);
+        }
+        for (key, value) in locale.keywords_map()? {
+            qualifiers.push(self.key_value_name(&key, &value)?);
+        }
+        let language = self.language_name(&locale.language().unwrap_or_default())?;
+        if qualifiers.is_empty() {
+            return Ok(language);
+        }
+        let qualifiers = qualifiers.join(self.separator.as_deref().unwrap_or(", "));
+        Ok(self
+            .pattern
+            .as_deref()
+            .unwrap_or("{0} ({1})")
+            .replace("{0}", &language)
+            .replace("{1}", &qualifiers))
+    }
+
+    /// Implements `uldn_languageDisplayName` from ICU4C.
+    ///
+    /// `code` is a language subtag, such as `fr`.
+    pub fn language_name(&self, code: &str) -> Result<String, common::Error> {
+        self.call_buffered_code_method(versioned_function!(uldn_languageDisplayName), code)
+    }
+
+    /// Implements `uldn_scriptDisplayName` from ICU4C.
+    ///
+    /// `code` is a script subtag, such as `Cyrl`.
+    pub fn script_name(&self, code: &str) -> Result<String, common::Error> {
+        self.call_buffered_code_method(versioned_function!(uldn_scriptDisplayName), code)
+    }
+
+    /// Implements `uldn_regionDisplayName` from ICU4C.
+    ///
+    /// `code` is a region subtag, such as `JP`.
+    pub fn region_name(&self, code: &str) -> Result<String, common::Error> {
+        self.call_buffered_code_method(versioned_function!(uldn_regionDisplayName), code)
+    }
+
+    /// Implements `uldn_variantDisplayName` from ICU4C.
+    ///
+    /// `code` is a variant subtag, such as `POSIX`.
+    pub fn variant_name(&self, code: &str) -> Result<String, common::Error> {
+        self.call_buffered_code_method(versioned_function!(uldn_variantDisplayName), code)
+    }
+
+    /// Implements `uldn_keyDisplayName` from ICU4C.
+    ///
+    /// `key` is a legacy keyword name, such as `collation`.
+    pub fn key_name(&self, key: &str) -> Result<String, common::Error> {
+        self.call_buffered_code_method(versioned_function!(uldn_keyDisplayName), key)
+    }
+
+    /// Implements `uldn_keyValueDisplayName` from ICU4C.
+    ///
+    /// `key` and `value` are a legacy keyword name and value, such as `calendar` and `japanese`.
+    pub fn key_value_name(&self, key: &str, value: &str) -> Result<String, common::Error> {
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_key_value_name,
+            LOCALE_CAPACITY,
+            [
+                ldn: *const sys::ULocaleDisplayNames,
+                key: *const raw::c_char,
+                value: *const raw::c_char,
+            ],
+            []
+        );
+        let key = str_to_cstring(key);
+        let value = str_to_cstring(value);
+        let uchar = buffered_uchar_key_value_name(
+            versioned_function!(uldn_keyValueDisplayName),
+            self.rep.as_ptr(),
+            key.as_ptr(),
+            value.as_ptr(),
+        )?;
+        String::try_from(&uchar)
+    }
+
+    /// Call a `uldn` method that takes a single code and renders its display name.
+    fn call_buffered_code_method(
+        &self,
+        uldn_method: unsafe extern "C" fn(
+            *const sys::ULocaleDisplayNames,
+            *const raw::c_char,
+            *mut sys::UChar,
+            i32,
+            *mut UErrorCode,
+        ) -> i32,
+        code: &str,
+    ) -> Result<String, common::Error> {
+        buffered_uchar_method_with_retry!(
+            buffered_uchar_code_method,
+            LOCALE_CAPACITY,
+            [
+                ldn: *const sys::ULocaleDisplayNames,
+                code: *const raw::c_char,
+            ],
+            []
+        );
+        let code = str_to_cstring(code);
+        let uchar = buffered_uchar_code_method(uldn_method, self.rep.as_ptr(), code.as_ptr())?;
+        String::try_from(&uchar)
+    }
+}
+
+/// A handle to a locale stored in a [LocaleInterner].
+///
+/// Handles are only meaningful to the interner that returned them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LocaleId(u32);
+
+/// Stores each distinct locale once, and hands out small [LocaleId] handles to them.
+///
+/// Useful where many values are tagged with one of few locales: each value can hold a 4-byte
+/// handle in place of its own copy of the locale ID.
+#[derive(Debug, Default)]
+pub struct LocaleInterner {
+    locales: Vec<ULoc>,
+    ids: HashMap<ULoc, LocaleId>,
+}
+
+impl LocaleInterner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the handle for `locale`, storing a copy of it if it was not interned before.
+    ///
+    /// Equal locales always get the same handle.
+    pub fn intern(&mut self, locale: &ULoc) -> LocaleId {
+        if let Some(id) = self.ids.get(locale) {
+            return *id;
+        }
+        let id = LocaleId(
+            self.locales
+                .len()
+                .try_into()
+                .expect("more than u32::MAX interned locales"),
+        );
+        self.locales.push(locale.clone());
+        self.ids.insert(locale.clone(), id);
+        id
+    }
+
+    /// Returns the locale for a handle returned by [intern](LocaleInterner::intern).
+    ///
+    /// Panics if `id` was returned by a different interner, and is out of range for this one.
+    pub fn resolve(&self, id: LocaleId) -> &ULoc {
+        &self.locales[id.0 as usize]
+    }
+}
+
+/// Removes a leading byte order mark, and leading and trailing ASCII whitespace, from `id`.
+fn trim_locale_id(id: &str) -> &str {
+    id.strip_prefix('\u{feff}')
+        .unwrap_or(id)
+        .trim_matches(|c: char| c.is_ascii_whitespace())
+}
+
+/// Applies the ICU case mapping `method` to `text`.
+///
+/// `method` is called with the source text, the destination buffer and the status, and returns
+/// the full length of the result.  It is called a second time with a larger buffer if the result
+/// did not fit into the first one.
+fn case_map(
+    text: &str,
+    method: impl Fn(&ustring::UChar, &mut [sys::UChar], &mut sys::UErrorCode) -> i32,
+) -> Result<String, common::Error> {
+    let src = ustring::UChar::try_from(text)?;
+    // Case mapping rarely changes the length of a string by much.
+    let mut dest: Vec<sys::UChar> = vec![0; src.len() + 16];
+    let mut status = common::Error::OK_CODE;
+    let mut full_len = method(&src, &mut dest, &mut status);
+    if status == sys::UErrorCode::U_BUFFER_OVERFLOW_ERROR {
+        dest.resize(full_len as usize, 0);
+        status = common::Error::OK_CODE;
+        full_len = method(&src, &mut dest, &mut status);
+    }
+    common::Error::ok_or_warning(status)?;
+    dest.truncate(full_len as usize);
+    String::from_utf16(&dest).map_err(|e| common::Error::wrapper(anyhow!(e)))
+}
+
+/// Infallibly converts a Rust string to a `CString`. If there's an interior NUL, the string is
+/// truncated up to that point.
+fn str_to_cstring(input: &str) -> ffi::CString {
+    ffi::CString::new(input)
+        .unwrap_or_else(|e| ffi::CString::new(&input[0..e.nul_position()]).unwrap())
+}
+
+/// Infallibly converts a `CString` to a Rust `String`. We can safely assume that any strings
+/// coming from ICU data are valid UTF-8.
+fn cstring_to_string(input: &ffi::CString) -> String {
+    input.to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, anyhow::Error};
+
+    #[test]
+    fn test_language() -> Result<(), Error> {
+        let loc = ULoc::try_from("es-CO")?;
+        assert_eq!(loc.language(), Some("es".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_language_absent() -> Result<(), Error> {
+        let loc = ULoc::for_language_tag("und-CO")?;
+        assert_eq!(loc.language(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_script() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr-Cyrl")?;
+        assert_eq!(loc.script(), Some("Cyrl".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_script_absent() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr")?;
+        assert_eq!(loc.script(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_require_script() -> Result<(), Error> {
+        assert_eq!(ULoc::try_from("sr_Cyrl")?.require_script()?, "Cyrl");
+        let loc = ULoc::try_from("en")?;
+        let error = loc.require_script().unwrap_err();
+        assert!(format!("{}", error).contains("has no script subtag"));
+        assert_eq!(loc.add_likely_subtags()?.require_script()?, "Latn");
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_type() -> Result<(), Error> {
+        assert_eq!(
+            ULoc::try_from("en_US")?.region_type()?,
+            RegionType::Territory
+        );
+        assert_eq!(
+            ULoc::try_from("es_419")?.region_type()?,
+            RegionType::Grouping
+        );
+        assert_eq!(
+            ULoc::try_from("en_021")?.region_type()?,
+            RegionType::Subcontinent
+        );
+        assert_eq!(ULoc::try_from("en_001")?.region_type()?, RegionType::World);
+        assert_eq!(
+            ULoc::try_from("en_150")?.region_type()?,
+            RegionType::Continent
+        );
+        assert_eq!(
+            ULoc::try_from("en_EU")?.region_type()?,
+            RegionType::Grouping
+        );
+        assert!(ULoc::try_from("en")?.region_type().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_containing_region() -> Result<(), Error> {
+        let loc = ULoc::try_from("en_US")?;
+        assert_eq!(loc.containing_region()?, Some("021".to_string()));
+        let loc = ULoc::try_from("en_021")?;
+        assert_eq!(loc.containing_region()?, Some("019".to_string()));
+        assert_eq!(ULoc::try_from("en_001")?.containing_region()?, None);
+        assert_eq!(ULoc::try_from("en")?.containing_region()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_country() -> Result<(), Error> {
+        let loc = ULoc::try_from("es-CO")?;
+        assert_eq!(loc.country(), Some("CO".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_country_absent() -> Result<(), Error> {
+        let loc = ULoc::try_from("es")?;
+        assert_eq!(loc.country(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_likely_script() -> Result<(), Error> {
+        assert_eq!(
+            ULoc::try_from("en")?.likely_script()?.as_deref(),
+            Some("Latn")
+        );
+        assert_eq!(
+            ULoc::try_from("ru")?.likely_script()?.as_deref(),
+            Some("Cyrl")
+        );
+        assert_eq!(
+            ULoc::try_from("sr_Latn")?.likely_script()?.as_deref(),
+            Some("Latn")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_transliterate() -> Result<(), Error> {
+        let ru = ULoc::try_from("ru")?;
+        assert_eq!(ru.transliterate("Привет", "Latn")?, "Privet");
+        assert_eq!(ru.transliterate("Привет", "Latin")?, "Privet");
+        assert!(ru.transliterate("Привет", "Nonsense").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sibling_locales() -> Result<(), Error> {
+        let siblings = ULoc::try_from("en_US")?.sibling_locales();
+        assert!(siblings.len() > 3, "{:?}", siblings);
+        assert!(siblings
+            .iter()
+            .all(|sibling| sibling.language().as_deref() == Some("en")));
+        assert_eq!(siblings[0], ULoc::try_from("en")?);
+        for tag in &["en_CA", "en_GB", "en_US"] {
+            assert!(siblings.contains(&ULoc::try_from(*tag)?), "{}", tag);
+        }
+        let regions: Vec<String> = siblings
+            .iter()
+            .map(|sibling| sibling.country().unwrap_or_default())
+            .collect();
+        let mut sorted = regions.clone();
+        sorted.sort();
+        assert_eq!(regions, sorted);
+        assert_eq!(ULoc::try_from("")?.sibling_locales(), vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_deprecated() -> Result<(), Error> {
+        for tag in &["iw", "iw_IL", "sh", "no_NO_NY", "de_DD"] {
+            assert!(ULoc::try_from(*tag)?.is_deprecated()?, "{}", tag);
+        }
+        for tag in &["he", "he_IL", "sr_Latn", "nn_NO", "de_DE", "en_US_POSIX"] {
+            assert!(!ULoc::try_from(*tag)?.is_deprecated()?, "{}", tag);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_likely_subtags_detailed() -> Result<(), Error> {
+        let cases = [
+            // Exact: entries of their own in the CLDR data.
+            ("en", "en_Latn_US", true),
+            ("zh_TW", "zh_Hant_TW", true),
+            ("und_RU", "ru_Cyrl_RU", true),
+            // Derived: the entry for the language, with the region put back.
+            ("en_CH", "en_Latn_CH", false),
+            ("sr_Latn", "sr_Latn_RS", false),
+            ("en_Latn_US", "en_Latn_US", false),
+            // No data at all.
+            ("xyz", "xyz", false),
+        ];
+        for (tag, maximized, exact) in &cases {
+            assert_eq!(
+                ULoc::try_from(*tag)?.likely_subtags_detailed()?,
+                (ULoc::try_from(*maximized)?, *exact),
+                "for {}",
+                tag
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_spellout() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        assert_eq!(en.spellout(123.0)?, "one hundred twenty-three");
+        assert_eq!(en.spellout(-2.5)?, "minus two point five");
+        assert_eq!(ULoc::try_from("fr")?.spellout(123.0)?, "cent vingt-trois");
+        // Longer than the initial buffer.
+        assert!(en.spellout(777_777_777_777_777.0)?.len() > LOCALE_CAPACITY);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_ordinal() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        for (n, expected) in &[
+            (1, "1st"),
+            (2, "2nd"),
+            (3, "3rd"),
+            (4, "4th"),
+            (11, "11th"),
+            (12, "12th"),
+            (13, "13th"),
+            (21, "21st"),
+            (22, "22nd"),
+            (111, "111th"),
+            (1001, "1,001st"),
+        ] {
+            assert_eq!(en.format_ordinal(*n)?, *expected, "n: {}", n);
+        }
+        assert_eq!(ULoc::try_from("fr")?.format_ordinal(1)?, "1er");
+        Ok(())
+    }
+
+    #[test]
+    fn test_currency_symbol() -> Result<(), Error> {
+        let en_us = ULoc::try_from("en_US")?;
+        assert_eq!(en_us.currency_symbol(Some("USD"))?, "$");
+        assert_eq!(en_us.currency_symbol(None)?, "$");
+        assert_eq!(en_us.currency_symbol(Some("EUR"))?, "\u{20ac}");
+        let de_de = ULoc::try_from("de_DE")?;
+        assert_eq!(de_de.currency_symbol(Some("EUR"))?, "\u{20ac}");
+        assert_eq!(de_de.currency_symbol(None)?, "\u{20ac}");
+        assert_eq!(
+            ULoc::try_from("en_CA")?.currency_symbol(Some("USD"))?,
+            "US$"
+        );
+        assert_eq!(ULoc::try_from("ja_JP")?.currency_symbol(None)?, "\u{ffe5}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_currency_display_name() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        assert_eq!(en.currency_display_name("USD", false)?, "US Dollar");
+        assert_eq!(en.currency_display_name("USD", true)?, "US dollars");
+        assert_eq!(en.currency_display_name("EUR", false)?, "Euro");
+        assert_eq!(en.currency_display_name("EUR", true)?, "euros");
+        assert_eq!(
+            ULoc::try_from("de")?.currency_display_name("USD", false)?,
+            "US-Dollar"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "icu_version_67_plus")]
+    #[test]
+    fn test_unit_display_name() -> Result<(), Error> {
+        use sys::UNumberUnitWidth::*;
+        for (id, unit, width, expected) in &[
+            (
+                "en",
+                "kilometer-per-hour",
+                UNUM_UNIT_WIDTH_FULL_NAME,
+                "kilometers per hour",
+            ),
+            ("en", "kilometer-per-hour", UNUM_UNIT_WIDTH_SHORT, "km/h"),
+            ("en", "foot", UNUM_UNIT_WIDTH_NARROW, "′"),
+            (
+                "de",
+                "kilometer-per-hour",
+                UNUM_UNIT_WIDTH_FULL_NAME,
+                "Kilometer pro Stunde",
+            ),
+            ("de", "meter", UNUM_UNIT_WIDTH_FULL_NAME, "Meter"),
+            ("de", "liter", UNUM_UNIT_WIDTH_SHORT, "l"),
+            (
+                "fr",
+                "foot-per-minute",
+                UNUM_UNIT_WIDTH_FULL_NAME,
+                "pieds par minute",
+            ),
+            ("ja", "kilometer", UNUM_UNIT_WIDTH_FULL_NAME, "キロメートル"),
+            ("ja", "kilometer-per-hour", UNUM_UNIT_WIDTH_SHORT, "km/h"),
+        ] {
+            let name = ULoc::try_from(*id)?.unit_display_name(unit, *width)?;
+            assert_eq!(name, *expected, "{} {} {:?}", id, unit, width);
+        }
+        let en = ULoc::try_from("en")?;
+        let error = en
+            .unit_display_name("not-a-unit", UNUM_UNIT_WIDTH_SHORT)
+            .expect_err("not a unit");
+        assert_eq!(
+            error.error_code(),
+            Some(UErrorCode::U_NUMBER_SKELETON_SYNTAX_ERROR)
+        );
+        let error = en
+            .unit_display_name("meter", UNUM_UNIT_WIDTH_HIDDEN)
+            .expect_err("hidden width");
+        assert_eq!(
+            error.error_code(),
+            Some(UErrorCode::U_ILLEGAL_ARGUMENT_ERROR)
+        );
+        // "時速2.5キロメートル" has the number in the middle of the name.
+        let error = ULoc::try_from("ja")?
+            .unit_display_name("kilometer-per-hour", UNUM_UNIT_WIDTH_FULL_NAME)
+            .expect_err("split name");
+        assert_eq!(error.error_code(), Some(UErrorCode::U_UNSUPPORTED_ERROR));
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_zone_display_name() -> Result<(), Error> {
+        let zone = "America/Los_Angeles";
+        for (id, style, expected) in &[
+            ("en_US", TimeZoneNameStyle::LongGeneric, "Pacific Time"),
+            ("en_US", TimeZoneNameStyle::ShortGeneric, "PT"),
+            (
+                "en_US",
+                TimeZoneNameStyle::LongStandard,
+                "Pacific Standard Time",
+            ),
+            ("en_US", TimeZoneNameStyle::ShortDaylight, "PDT"),
+            ("en_US", TimeZoneNameStyle::ExemplarLocation, "Los Angeles"),
+            (
+                "de_DE",
+                TimeZoneNameStyle::LongGeneric,
+                "Nordamerikanische Westküstenzeit",
+            ),
+            (
+                "de_DE",
+                TimeZoneNameStyle::LongDaylight,
+                "Nordamerikanische Westküsten-Sommerzeit",
+            ),
+        ] {
+            let name = ULoc::try_from(*id)?.time_zone_display_name(zone, *style)?;
+            assert_eq!(name, *expected, "{} {:?}", id, style);
+        }
+        let error = ULoc::try_from("en_US")?
+            .time_zone_display_name("Mars/Olympus_Mons", TimeZoneNameStyle::LongGeneric)
+            .expect_err("not a time zone");
+        assert_eq!(
+            error.error_code(),
+            Some(UErrorCode::U_ILLEGAL_ARGUMENT_ERROR)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_era_names() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        assert_eq!(en.era_names(None)?, vec!["BC", "AD"]);
+        assert_eq!(en.era_names(Some("gregorian"))?, vec!["BC", "AD"]);
+        assert_eq!(en.era_names(Some("buddhist"))?, vec!["BE"]);
+        let japanese = en.era_names(Some("japanese"))?;
+        assert_eq!(japanese.last().map(String::as_str), Some("Reiwa"));
+        assert_eq!(
+            ULoc::try_from("th_TH")?.era_names(None)?,
+            vec!["\u{e1e}.\u{e28}."]
+        );
+        assert_eq!(
+            ULoc::try_from("de")?.era_names(Some("gregory"))?,
+            vec!["v. Chr.", "n. Chr."]
+        );
+        let error = en.era_names(Some("martian")).expect_err("not a calendar");
+        assert_eq!(
+            error.error_code(),
+            Some(UErrorCode::U_ILLEGAL_ARGUMENT_ERROR)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_for_script() -> Result<(), Error> {
+        for (id, expected) in &[
+            ("zh_Hant", Some("TW")),
+            ("zh_Hans", Some("CN")),
+            ("zh_Hant_HK", Some("TW")),
+            ("sr_Latn", Some("RS")),
+            ("und_Cyrl", Some("RU")),
+            ("zh", None),
+        ] {
+            let region = ULoc::try_from(*id)?.region_for_script()?;
+            assert_eq!(region.as_deref(), *expected, "id: {}", id);
+        }
+        Ok(())
+    }
+
+    // This test yields a different result in ICU versions prior to 64:
+    // "zh-Latn@collation=pinyin".
+    #[cfg(features = "icu_version_64_plus")]
+    #[test]
+    fn test_variant() -> Result<(), Error> {
+        let loc = ULoc::try_from("zh-Latn-pinyin")?;
+        assert_eq!(
+            loc.variant(),
+            Some("PINYIN".to_string()),
+            "locale was: {:?}",
+            loc
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_likely_region() -> Result<(), Error> {
+        let cases = [
+            ("en", "US"),
+            ("pt", "BR"),
+            ("fr", "FR"),
+            ("ja", "JP"),
+            ("zh_Hant", "TW"),
+            ("en_GB", "GB"),
+        ];
+        for (tag, region) in &cases {
+            assert_eq!(
+                ULoc::try_from(*tag)?.likely_region()?.as_deref(),
+                Some(*region),
+                "for {}",
+                tag
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_variant() -> Result<(), Error> {
+        let loc = ULoc::try_from("zh_Latn")?;
+        let with_variant = loc.with_variant("PINYIN")?;
+        assert_eq!(with_variant, ULoc::try_from("zh_Latn__PINYIN")?);
+        assert_eq!(with_variant.variant(), Some("PINYIN".to_string()));
+        assert_eq!(with_variant.clear_variant()?, loc);
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_variant_preserves_country_and_keywords() -> Result<(), Error> {
+        let loc = ULoc::try_from("en_US_POSIX@calendar=japanese")?;
+        assert_eq!(
+            loc.clear_variant()?,
+            ULoc::try_from("en_US@calendar=japanese")?
+        );
+        assert_eq!(
+            loc.with_variant("BOONT")?,
+            ULoc::try_from("en_US_BOONT@calendar=japanese")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_variant_absent() -> Result<(), Error> {
+        let loc = ULoc::try_from("zh-Latn")?;
+        assert_eq!(loc.variant(), None);
+        Ok(())
+    }
+
+    /// Serializes the tests that change the process-wide default locale.
+    fn default_locale_lock() -> std::sync::MutexGuard<'static, ()> {
+        static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[test]
+    fn test_default_locale() {
+        let _lock = default_locale_lock();
+        let loc = ULoc::try_from("fr-fr").expect("get fr_FR locale");
+        set_default(&loc).expect("successful set of locale");
+        assert_eq!(get_default().label(), loc.label());
+        assert_eq!(loc.label(), "fr_FR", "The locale should get canonicalized");
+        let loc = ULoc::try_from("en-us").expect("get en_US locale");
+        set_default(&loc).expect("successful set of locale");
+        assert_eq!(get_default().label(), loc.label());
+    }
+
+    #[test]
+    fn test_eq_c_str() -> Result<(), Error> {
+        let loc = ULoc::try_from("en-US")?;
+        assert!(loc.eq_c_str(&ffi::CString::new("en_US")?));
+        assert!(!loc.eq_c_str(&ffi::CString::new("en-US")?));
+        assert!(!loc.eq_c_str(&ffi::CString::new("en")?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr_Latn_RS@calendar=japanese")?;
+        assert_eq!(
+            format!("{:?}", loc),
+            r#"ULoc { repr: "sr_Latn_RS@calendar=japanese" }"#
+        );
+        let alternate = format!("{:#?}", loc);
+        assert!(alternate.contains(r#"language: "sr""#), "{}", alternate);
+        assert!(alternate.contains(r#"country: "RS""#), "{}", alternate);
+        assert!(
+            alternate.contains(r#""calendar": "japanese""#),
+            "{}",
+            alternate
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_repr_bytes() -> Result<(), Error> {
+        let loc = ULoc::for_language_tag("sr-Cyrl-RS-u-ca-hebrew")?;
+        assert_eq!(loc.repr_bytes(), loc.label().as_bytes());
+        assert_eq!(loc.repr_bytes(), loc.as_c_str().as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_posix() -> Result<(), Error> {
+        assert_eq!(ULoc::from_posix("en_US.UTF-8")?.label(), "en_US");
+        assert_eq!(ULoc::from_posix("de_DE")?.label(), "de_DE");
+        assert_eq!(ULoc::from_posix("C")?.label(), "en_US_POSIX");
+        assert_eq!(ULoc::from_posix("POSIX")?.label(), "en_US_POSIX");
+        assert_eq!(ULoc::from_posix("C.UTF-8")?.label(), "en_US_POSIX");
+        assert_eq!(
+            ULoc::from_posix("ca_ES.UTF-8@valencia")?.label(),
+            "ca_ES_VALENCIA"
+        );
+        assert_eq!(ULoc::from_posix("nn_NO@nynorsk")?.label(), "nn_NO_NY");
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_default_from_environment() -> Result<(), Error> {
+        const VARIABLES: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+        let _lock = default_locale_lock();
+        let previous_default = get_default();
+        let previous_env: Vec<_> = VARIABLES.iter().map(std::env::var_os).collect();
+        let set_env = |values: [Option<&str>; 3]| {
+            for (name, value) in VARIABLES.iter().zip(values.iter()) {
+                match value {
+                    Some(value) => std::env::set_var(name, value),
+                    None => std::env::remove_var(name),
+                }
+            }
+        };
+
+        set_env([None, Some("fr_FR.UTF-8"), Some("de_DE.UTF-8")]);
+        let from_messages = set_default_from_environment()?;
+        let default_from_messages = get_default();
+
+        set_env([
+            Some("es_MX.UTF-8"),
+            Some("fr_FR.UTF-8"),
+            Some("de_DE.UTF-8"),
+        ]);
+        let from_all = set_default_from_environment()?;
+
+        set_env([Some(""), None, Some("de_DE.UTF-8")]);
+        let from_lang = set_default_from_environment()?;
+
+        set_env([None, None, None]);
+        let from_nothing = set_default_from_environment()?;
+
+        for (name, value) in VARIABLES.iter().zip(previous_env) {
+            match value {
+                Some(value) => std::env::set_var(name, value),
+                None => std::env::remove_var(name),
+            }
+        }
+        set_default(&previous_default)?;
+
+        assert_eq!(from_messages.label(), "fr_FR");
+        assert_eq!(default_from_messages.label(), "fr_FR");
+        assert_eq!(from_all.label(), "es_MX");
+        assert_eq!(from_lang.label(), "de_DE");
+        assert_eq!(from_nothing.label(), "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_name() -> Result<(), Error> {
+        let loc = ULoc::try_from("fr_FR")?;
+        assert_eq!(
+            loc.display_name(&ULoc::try_from("en_US")?)?,
+            "French (France)"
+        );
+        assert_eq!(
+            loc.display_name(&ULoc::try_from("de_DE")?)?,
+            "Französisch (Frankreich)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_name_strict() -> Result<(), Error> {
+        let loc = ULoc::try_from("fr_FR")?;
+        assert_eq!(
+            loc.display_name_strict(&ULoc::try_from("en")?)?,
+            "French (France)"
+        );
+        assert_eq!(
+            loc.display_name_strict(&ULoc::try_from("en_XX")?)
+                .unwrap_err()
+                .error_code(),
+            Some(UErrorCode::U_USING_FALLBACK_WARNING)
+        );
+
+        // A display locale without data gets the default locale's, so pin the default.
+        let _lock = default_locale_lock();
+        let previous = get_default();
+        set_default(&ULoc::try_from("de_DE")?)?;
+        let strict = loc.display_name_strict(&ULoc::try_from("xx")?);
+        let lenient = loc.display_name(&ULoc::try_from("xx")?);
+        set_default(&previous)?;
+        assert_eq!(
+            strict.unwrap_err().error_code(),
+            Some(UErrorCode::U_USING_DEFAULT_WARNING)
+        );
+        // The lenient variant accepts the default locale's data.
+        assert_eq!(lenient?, "Französisch (Frankreich)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_name_preferring() -> Result<(), Error> {
+        let loc = ULoc::try_from("fr_FR")?;
+        let display_locales = locales_from_tags(vec!["xx", "de", "en"])?;
+        assert_eq!(
+            loc.display_name_preferring(&display_locales)?,
+            "Französisch (Frankreich)"
+        );
+        // en_US only has the display data it inherits from en, and de_DE from de, so the
+        // name in the last display locale is used.
+        let display_locales = locales_from_tags(vec!["en_US", "de_DE"])?;
+        assert_eq!(
+            loc.display_name_preferring(&display_locales)?,
+            "Französisch (Frankreich)"
+        );
+        let display_locales = locales_from_tags(vec!["en_US", "de"])?;
+        assert_eq!(
+            loc.display_name_preferring(&display_locales)?,
+            "Französisch (Frankreich)"
+        );
+        let display_locales = locales_from_tags(vec!["en_US", "en"])?;
+        assert_eq!(
+            loc.display_name_preferring(&display_locales)?,
+            "French (France)"
+        );
+        assert!(loc.display_name_preferring(&[]).is_err());
+
+        // Without any data, the name in the last display locale is the default locale's.
+        let _lock = default_locale_lock();
+        let previous = get_default();
+        set_default(&ULoc::try_from("de_DE")?)?;
+        let display_locales = locales_from_tags(vec!["xx", "yy"])?;
+        let actual = loc.display_name_preferring(&display_locales);
+        set_default(&previous)?;
+        assert_eq!(actual?, "Französisch (Frankreich)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_name_in_default() -> Result<(), Error> {
+        let _lock = default_locale_lock();
+        let previous = get_default();
+        set_default(&ULoc::try_from("de_DE")?)?;
+        let actual = ULoc::try_from("fr_FR")?.display_name_in_default();
+        set_default(&previous)?;
+        assert_eq!(actual?, "Französisch (Frankreich)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_locale_display_names() -> Result<(), Error> {
+        let names = LocaleDisplayNames::try_new(
+            &ULoc::try_from("en_US")?,
+            UDialectHandling::ULDN_STANDARD_NAMES,
+        )?;
+        assert_eq!(
+            names.locale_display_name(&ULoc::try_from("fr_FR")?)?,
+            "French (France)"
+        );
+        assert_eq!(names.language_name("fr")?, "French");
+        assert_eq!(names.script_name("Cyrl")?, "Cyrillic");
+        assert_eq!(names.region_name("JP")?, "Japan");
+        assert_eq!(names.key_name("calendar")?, "Calendar");
+        assert_eq!(
+            names.key_value_name("calendar", "japanese")?,
+            "Japanese Calendar"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_parts() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr_Latn_RS_REVISED")?;
+        let en = ULoc::try_from("en")?;
+        assert_eq!(loc.display_language(&en)?, "Serbian");
+        assert_eq!(loc.display_script(&en)?, "Latin");
+        assert_eq!(loc.display_country(&en)?, "Serbia");
+        assert_eq!(loc.display_variant(&en)?, "Revised Orthography");
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_display_name() -> Result<(), Error> {
+        let fr = ULoc::try_from("fr")?;
+        assert_eq!(
+            ULoc::try_from("en_US")?.region_display_name(&fr)?,
+            "États-Unis"
+        );
+        let en = ULoc::try_from("en")?;
+        assert_eq!(
+            ULoc::try_from("de_CH")?.region_display_name(&en)?,
+            "Switzerland"
+        );
+        assert!(ULoc::try_from("en")?.region_display_name(&fr).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_parts_in_default() -> Result<(), Error> {
+        let _lock = default_locale_lock();
+        let previous = get_default();
+        set_default(&ULoc::try_from("de_DE")?)?;
+        let loc = ULoc::try_from("sr_Latn_RS_REVISED")?;
+        let actual = (
+            loc.display_language_in_default(),
+            loc.display_script_in_default(),
+            loc.display_country_in_default(),
+            loc.display_variant_in_default(),
+        );
+        set_default(&previous)?;
+        assert_eq!(actual.0?, "Serbisch");
+        assert_eq!(actual.1?, "Lateinisch");
+        assert_eq!(actual.2?, "Serbien");
+        assert_eq!(actual.3?, "Revidierte Rechtschreibung");
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolved_locale() -> Result<(), Error> {
+        let de_at = ULoc::try_from("de_AT")?;
+        assert_eq!(
+            de_at.resolved_locale(ResourceKind::Collation)?,
+            (de_at.clone(), ULoc::try_from("")?)
+        );
+        let (valid, _) = de_at.resolved_locale(ResourceKind::NumberFormat)?;
+        assert_eq!(valid, de_at);
+        let (valid, _) = de_at.resolved_locale(ResourceKind::Calendar)?;
+        assert_eq!(valid, de_at);
+        // No data at all for the region, so the valid locale falls back too.
+        let (valid, actual) =
+            ULoc::try_from("de_AQ")?.resolved_locale(ResourceKind::NumberFormat)?;
+        assert_eq!(valid, ULoc::try_from("de")?);
+        assert_eq!(actual, ULoc::try_from("de")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_collation_reorder_codes() -> Result<(), Error> {
+        let codes = ULoc::try_from("ja")?.collation_reorder_codes()?;
+        assert!(!codes.is_empty());
+        assert_eq!(codes[0], sys::UScriptCode::USCRIPT_LATIN as i32);
+        assert_eq!(ULoc::try_from("en")?.collation_reorder_codes()?, vec![]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_collation_key() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        let primary = sys::UCollationStrength::UCOL_PRIMARY;
+        let tertiary = sys::UCollationStrength::UCOL_TERTIARY;
+        assert_eq!(
+            en.collation_key("café", primary)?,
+            en.collation_key("cafe", primary)?
+        );
+        assert_ne!(
+            en.collation_key("café", tertiary)?,
+            en.collation_key("cafe", tertiary)?
+        );
+        assert!(en.collation_key("cafe", tertiary)? < en.collation_key("café", tertiary)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare() -> Result<(), Error> {
+        let tertiary = sys::UCollationStrength::UCOL_TERTIARY;
+        let de = ULoc::try_from("de")?;
+        let phonebook = ULoc::try_from("de@collation=phonebook")?;
+        assert_eq!(de.compare("ä", "z", tertiary)?, Ordering::Less);
+        assert_eq!(phonebook.compare("ä", "z", tertiary)?, Ordering::Less);
+        // Standard collation sorts "ü" like "u", phonebook collation like "ue".
+        assert_eq!(de.compare("Müller", "Muff", tertiary)?, Ordering::Greater);
+        assert_eq!(
+            phonebook.compare("Müller", "Muff", tertiary)?,
+            Ordering::Less
+        );
+        let primary = sys::UCollationStrength::UCOL_PRIMARY;
+        assert_eq!(de.compare("Ä", "a", primary)?, Ordering::Equal);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_list() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        assert_eq!(en.format_list(&["A", "B"])?, "A and B");
+        assert_eq!(en.format_list(&["A", "B", "C"])?, "A, B, and C");
+        let de = ULoc::try_from("de")?;
+        assert_eq!(de.format_list(&["A", "B"])?, "A und B");
+        assert_eq!(de.format_list(&["A", "B", "C"])?, "A, B und C");
+        Ok(())
+    }
+
+    #[test]
+    fn test_plural_category() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        assert_eq!(en.plural_category(1.0, PluralKind::Cardinal)?, "one");
+        assert_eq!(en.plural_category(2.0, PluralKind::Cardinal)?, "other");
+        assert_eq!(en.plural_category(2.0, PluralKind::Ordinal)?, "two");
+        assert_eq!(en.plural_category(3.0, PluralKind::Ordinal)?, "few");
+        let ru = ULoc::try_from("ru")?;
+        assert_eq!(ru.plural_category(1.0, PluralKind::Cardinal)?, "one");
+        assert_eq!(ru.plural_category(2.0, PluralKind::Cardinal)?, "few");
+        assert_eq!(ru.plural_category(5.0, PluralKind::Cardinal)?, "many");
+        assert_eq!(ru.plural_category(2.0, PluralKind::Ordinal)?, "other");
+        Ok(())
+    }
+
+    #[test]
+    fn test_plural_keywords() -> Result<(), Error> {
+        let ja = ULoc::try_from("ja")?;
+        assert_eq!(ja.plural_keywords(PluralKind::Cardinal)?, vec!["other"]);
+        let ar = ULoc::try_from("ar")?;
+        let mut keywords = ar.plural_keywords(PluralKind::Cardinal)?;
+        keywords.sort();
+        assert_eq!(keywords, vec!["few", "many", "one", "other", "two", "zero"]);
+        let en = ULoc::try_from("en")?;
+        assert_eq!(en.plural_keywords(PluralKind::Ordinal)?.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_base_direction() -> Result<(), Error> {
+        let ar = ULoc::try_from("ar")?;
+        let en = ULoc::try_from("en")?;
+        assert_eq!(ar.base_direction("Hello"), sys::UBiDiDirection::UBIDI_LTR);
+        assert_eq!(en.base_direction("שלום"), sys::UBiDiDirection::UBIDI_RTL);
+        assert_eq!(
+            en.base_direction("Hello שלום"),
+            sys::UBiDiDirection::UBIDI_MIXED
+        );
+        // Text with no strong characters takes the direction of the locale.
+        assert_eq!(ar.base_direction("!?"), sys::UBiDiDirection::UBIDI_RTL);
+        assert_eq!(en.base_direction("!?"), sys::UBiDiDirection::UBIDI_LTR);
+        Ok(())
+    }
+
+    #[test]
+    fn test_case_mapping() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        let tr = ULoc::try_from("tr")?;
+        assert_eq!(en.to_uppercase("i")?, "I");
+        assert_eq!(tr.to_uppercase("i")?, "İ");
+        assert_eq!(en.to_lowercase("I")?, "i");
+        assert_eq!(tr.to_lowercase("I")?, "ı");
+        // The result is longer than the input, and longer than the first guess at its length.
+        let long = "ß".repeat(40);
+        assert_eq!(en.to_uppercase(&long)?, "SS".repeat(40));
+        assert_eq!(en.to_uppercase("")?, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_title_case() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        assert_eq!(en.to_title_case("hello world")?, "Hello World");
+        assert_eq!(en.to_title_case("HELLO wORLD")?, "Hello World");
+        assert_eq!(en.to_title_case("ijsland")?, "Ijsland");
+        let nl = ULoc::try_from("nl")?;
+        assert_eq!(nl.to_title_case("ijsland")?, "IJsland");
+        Ok(())
+    }
+
+    #[test]
+    fn test_word_boundaries() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        assert_eq!(en.word_boundaries("Hello, world")?, vec![0, 5, 6, 7, 12]);
+        assert_eq!(en.word_boundaries("")?, vec![0]);
+
+        // "Hello" and a polite particle, without a space in between.
+        let text = "สวัสดีครับ";
+        assert_eq!(text.split_whitespace().count(), 1);
+        let th = ULoc::try_from("th")?;
+        let boundaries = th.word_boundaries(text)?;
+        assert_eq!(boundaries, vec![0, "สวัสดี".len(), text.len()]);
+        // Multi-byte characters outside the BMP are reported at byte offsets too.
+        assert_eq!(en.word_boundaries("😀 a")?, vec![0, 4, 5, 6]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cmp_by_display_name() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        let mut locales = vec![
+            ULoc::try_from("de")?,
+            ULoc::try_from("fr")?,
+            ULoc::try_from("es")?,
+        ];
+        locales.sort_by(|a, b| a.cmp_by_display_name(b, &en).unwrap());
+        // French, German, Spanish.
+        assert_eq!(
+            locales,
+            vec![
+                ULoc::try_from("fr")?,
+                ULoc::try_from("de")?,
+                ULoc::try_from("es")?,
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_maximize_all_minimize_all() -> Result<(), Error> {
+        let locales = locales_from_tags(vec!["en", "sr", "zh_TW"])?;
+        let maximized = maximize_all(&locales)?;
+        assert_eq!(
+            maximized,
+            locales_from_tags(vec!["en_Latn_US", "sr_Cyrl_RS", "zh_Hant_TW"])?
+        );
+        assert_eq!(minimize_all(&maximized)?, locales);
+        assert_eq!(maximize_all(&[])?, vec![]);
+
+        let too_long = ULoc {
+            repr: "x".repeat(200),
+        };
+        let error = maximize_all(&[locales[0].clone(), too_long]).unwrap_err();
+        assert!(format!("{}", error).starts_with("at index 1"));
+        Ok(())
+    }
 
-    // Having no match is a valid if disappointing result.
-    if accept_result == UAcceptResult::ULOC_ACCEPT_FAILED {
-        return Ok((None, accept_result));
+    #[test]
+    fn test_parse_accept_language() -> Result<(), Error> {
+        let parsed = parse_accept_language("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5")?;
+        assert_eq!(
+            parsed,
+            vec![
+                (ULoc::try_from("fr_CH")?, 1.0),
+                (ULoc::try_from("fr")?, 0.9),
+                (ULoc::try_from("en")?, 0.8),
+                (ULoc::try_from("de")?, 0.7),
+                (ULoc::try_from("")?, 0.5),
+            ]
+        );
+
+        let parsed = parse_accept_language("de;q=0.5,en-GB ; Q=0.8, ,ja;q=bogus,es;q=7")?;
+        assert_eq!(
+            parsed,
+            vec![
+                (ULoc::try_from("ja")?, 1.0),
+                (ULoc::try_from("es")?, 1.0),
+                (ULoc::try_from("en_GB")?, 0.8),
+                (ULoc::try_from("de")?, 0.5),
+            ]
+        );
+
+        assert_eq!(parse_accept_language("")?, vec![]);
+        assert!(parse_accept_language("en, $$").is_err());
+        Ok(())
     }
 
-    matched_locale
-        .and_then(|s| ULoc::try_from(s.as_str()))
-        .map(|uloc| (Some(uloc), accept_result))
-}
+    #[test]
+    fn test_tags_equal() {
+        let tags = ["en-US", "en_US", "EN-us"];
+        for a in &tags {
+            for b in &tags {
+                assert!(tags_equal(a, b), "{} and {} should be equal", a, b);
+            }
+        }
+        assert!(!tags_equal("en-US", "en-GB"));
+    }
 
-/// Implements `uloc_toUnicodeLocaleKey` from ICU4C.
-pub fn to_unicode_locale_key(legacy_keyword: &str) -> Option<String> {
-    let legacy_keyword = str_to_cstring(legacy_keyword);
-    let unicode_keyword: Option<ffi::CString> = unsafe {
-        let ptr = versioned_function!(uloc_toUnicodeLocaleKey)(legacy_keyword.as_ptr());
-        ptr.as_ref().map(|ptr| ffi::CStr::from_ptr(ptr).to_owned())
-    };
-    unicode_keyword.map(|cstring| cstring_to_string(&cstring))
-}
+    #[test]
+    fn test_is_canonical_bcp47() {
+        assert!(is_canonical_bcp47("en-US"));
+        assert!(is_canonical_bcp47("sr-Latn-RS-u-ca-japanese"));
+        assert!(!is_canonical_bcp47("en-us"));
+        assert!(!is_canonical_bcp47("en_US"));
+        assert!(!is_canonical_bcp47("iw-IL"));
+        assert!(!is_canonical_bcp47("en--US"));
+    }
 
-/// Implements `uloc_toUnicodeLocaleType` from ICU4C.
-pub fn to_unicode_locale_type(legacy_keyword: &str, legacy_value: &str) -> Option<String> {
-    let legacy_keyword = str_to_cstring(legacy_keyword);
-    let legacy_value = str_to_cstring(legacy_value);
-    let unicode_value: Option<ffi::CString> = unsafe {
-        let ptr = versioned_function!(uloc_toUnicodeLocaleType)(
-            legacy_keyword.as_ptr(),
-            legacy_value.as_ptr(),
+    #[test]
+    fn test_sort_by_display_name() -> Result<(), Error> {
+        let mut locales = locales_from_tags(vec!["ja", "fr", "ee", "de", "en"])?;
+        sort_by_display_name(&mut locales, &ULoc::try_from("fr")?)?;
+        // allemand, anglais, éwé, français, japonais.
+        assert_eq!(
+            locales,
+            locales_from_tags(vec!["de", "en", "ee", "fr", "ja"])?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_locale_display_names_overrides() -> Result<(), Error> {
+        let en = ULoc::try_from("en")?;
+        let loc = ULoc::try_from("sr_Latn_RS")?;
+        let names = LocaleDisplayNames::try_new(&en, UDialectHandling::ULDN_STANDARD_NAMES)?;
+        assert_eq!(names.locale_display_name(&loc)?, "Serbian (Latin, Serbia)");
+
+        let names = LocaleDisplayNames::try_new(&en, UDialectHandling::ULDN_STANDARD_NAMES)?
+            .with_separator(" / ");
+        assert_eq!(names.locale_display_name(&loc)?, "Serbian (Latin / Serbia)");
+        assert_eq!(
+            names.locale_display_name(&ULoc::try_from("sr")?)?,
+            "Serbian"
+        );
+
+        let names = LocaleDisplayNames::try_new(&en, UDialectHandling::ULDN_STANDARD_NAMES)?
+            .with_pattern("{0} [{1}]");
+        assert_eq!(
+            names.locale_display_name(&ULoc::try_from("en_US_POSIX@calendar=japanese")?)?,
+            "English [United States, Computer, Japanese Calendar]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_likely_subtags() {
+        let loc = ULoc::try_from("en-US").expect("get en_US locale");
+        let with_likely_subtags = loc.add_likely_subtags().expect("should add likely subtags");
+        let expected = ULoc::try_from("en_Latn_US").expect("get en_Latn_US locale");
+        assert_eq!(with_likely_subtags.label(), expected.label());
+    }
+
+    #[test]
+    fn test_minimize_subtags() {
+        let loc = ULoc::try_from("sr_Cyrl_RS").expect("get sr_Cyrl_RS locale");
+        let minimized_subtags = loc.minimize_subtags().expect("should minimize subtags");
+        let expected = ULoc::try_from("sr").expect("get sr locale");
+        assert_eq!(minimized_subtags.label(), expected.label());
+    }
+
+    #[test]
+    fn test_fingerprint() -> Result<(), Error> {
+        assert_eq!(
+            ULoc::try_from("en-US")?.fingerprint(),
+            0x8481_66bf_bc23_fa67
+        );
+        assert_eq!(ULoc::try_from("")?.fingerprint(), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(
+            ULoc::try_from("fr_FR")?.fingerprint(),
+            ULoc::try_from("fr-FR")?.fingerprint()
+        );
+        assert_ne!(
+            ULoc::try_from("fr_FR")?.fingerprint(),
+            ULoc::try_from("fr_CA")?.fingerprint()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_locale_interner() -> Result<(), Error> {
+        let mut interner = LocaleInterner::new();
+        let en = interner.intern(&ULoc::try_from("en-US")?);
+        let fr = interner.intern(&ULoc::try_from("fr_FR")?);
+        assert_ne!(en, fr);
+        assert_eq!(interner.intern(&ULoc::try_from("en_US")?), en);
+        assert_eq!(interner.resolve(en), &ULoc::try_from("en_US")?);
+        assert_eq!(interner.resolve(fr), &ULoc::try_from("fr_FR")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_minimized_language_tag() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr_Cyrl_RS@calendar=gregory")?;
+        assert_eq!(loc.minimized_language_tag()?, "sr-u-ca-gregory");
+        let loc = ULoc::try_from("zh_Hant_TW@calendar=buddhist;numbers=hanidec")?;
+        assert_eq!(
+            loc.minimized_language_tag()?,
+            "zh-TW-u-ca-buddhist-nu-hanidec"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_language_tag() {
+        let loc = ULoc::try_from("sr_Cyrl_RS").expect("get sr_Cyrl_RS locale");
+        let language_tag = loc
+            .to_language_tag(true)
+            .expect("should convert to language tag");
+        assert_eq!(language_tag, "sr-Cyrl-RS".to_string());
+    }
+
+    #[test]
+    fn test_canonicalize_reporting() -> Result<(), Error> {
+        let en_us = ULoc::try_from("en_US")?;
+        assert_eq!(
+            ULoc::canonicalize_reporting("en-US")?,
+            (en_us.clone(), true)
+        );
+        assert_eq!(
+            ULoc::canonicalize_reporting("EN_us")?,
+            (en_us.clone(), true)
+        );
+        assert_eq!(
+            ULoc::canonicalize_reporting("en_US")?,
+            (en_us.clone(), false)
+        );
+        assert_eq!(ULoc::canonicalize_reporting(" en_US\n")?, (en_us, false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_available() -> Result<(), Error> {
+        assert_eq!(ULoc::try_from_available("en-US")?.label(), "en_US");
+        assert_eq!(
+            ULoc::try_from_available("en-US-u-ca-japanese")?.label(),
+            "en_US@calendar=japanese"
+        );
+        assert_eq!(
+            ULoc::try_from_available("de_CH_1901")?.label(),
+            "de_CH_1901"
+        );
+        assert!(ULoc::try_from("xx-YY").is_ok());
+        assert!(ULoc::try_from_available("xx-YY").is_err());
+        assert!(ULoc::try_from_available("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_needs_complex_shaping() -> Result<(), Error> {
+        for id in &["ar", "hi", "th_TH", "ur_PK", "pa_Guru", "pa_Arab_PK"] {
+            assert!(ULoc::try_from(*id)?.needs_complex_shaping()?, "id: {}", id);
+        }
+        for id in &["en", "ru", "he", "ja", "sr_Latn", "xyz"] {
+            assert!(!ULoc::try_from(*id)?.needs_complex_shaping()?, "id: {}", id);
+        }
+        assert!(COMPLEX_SHAPING_SCRIPTS.windows(2).all(|w| w[0] < w[1]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_script_sample() -> Result<(), Error> {
+        for (id, expected) in &[("ja", "\u{304b}"), ("ar", "\u{628}"), ("he_IL", "\u{5d0}")] {
+            let sample = ULoc::try_from(*id)?.script_sample()?;
+            assert_eq!(sample.as_deref(), Some(*expected), "id: {}", id);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_available_for() -> Result<(), Error> {
+        let en_gb = ULoc::try_from("en_GB")?;
+        assert!(en_gb.is_available_for(IcuService::DateFormat));
+        assert!(en_gb.is_available_for(IcuService::NumberFormat));
+        assert!(en_gb.is_available_for(IcuService::BreakIterator));
+        assert!(!en_gb.is_available_for(IcuService::Collator));
+        let de = ULoc::try_from("de@collation=phonebook")?;
+        assert!(de.is_available_for(IcuService::Collator));
+        assert!(!ULoc::try_from("xx_YY")?.is_available_for(IcuService::DateFormat));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cldr_parent() -> Result<(), Error> {
+        for (id, expected) in &[
+            ("en_GB", Some("en_001")),
+            ("en_001", Some("en")),
+            ("en_CH", Some("en_150")),
+            ("es_MX", Some("es_419")),
+            ("zh_Hant", Some("")),
+            ("fr_CA@calendar=gregorian", Some("fr")),
+            ("en_GB_SCOUSE", Some("en_GB")),
+            ("fr", Some("")),
+            ("", None),
+        ] {
+            let parent = ULoc::try_from(*id)?.cldr_parent()?;
+            assert_eq!(parent.as_ref().map(ULoc::label), *expected, "id: {}", id);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_base_language_tag() -> Result<(), Error> {
+        let loc = ULoc::for_language_tag("th-TH-u-ca-buddhist")?;
+        assert_eq!(loc.base_name()?.label(), "th_TH");
+        assert_eq!(loc.base_language_tag()?, "th-TH");
+        let loc = ULoc::for_language_tag("sr-Latn-RS-u-nu-latn-x-private")?;
+        assert_eq!(loc.base_language_tag()?, "sr-Latn-RS");
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_windows_locale_name() -> Result<(), Error> {
+        for (name, expected) in &[
+            ("en-US", "en_US"),
+            ("zh-CHS", "zh_Hans"),
+            ("zh-cht", "zh_Hant"),
+            ("de-DE_phoneb", "de_DE@collation=phonebook"),
+            ("es-ES_tradnl", "es_ES@collation=traditional"),
+            ("zh-TW_pronun", "zh_TW@collation=zhuyin"),
+        ] {
+            assert_eq!(
+                ULoc::from_windows_locale_name(name)?.label(),
+                *expected,
+                "name: {}",
+                name
+            );
+        }
+        assert!(ULoc::from_windows_locale_name("hu-HU_tchncl").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_windows_locale_name() -> Result<(), Error> {
+        for (id, expected) in &[
+            ("en_US", "en-US"),
+            ("zh_Hans", "zh-CHS"),
+            ("zh_Hant", "zh-CHT"),
+            ("zh_Hant_TW", "zh-Hant-TW"),
+            ("zh_TW@collation=zhuyin", "zh-TW_pronun"),
+            (
+                "de_DE@calendar=buddhist;collation=phonebook",
+                "de-DE_phoneb",
+            ),
+            ("sv_SE@collation=search", "sv-SE"),
+        ] {
+            let loc = ULoc::try_from(*id)?;
+            assert_eq!(loc.to_windows_locale_name()?, *expected, "id: {}", id);
+        }
+        for id in &["zh_Hant", "zh_Hans", "zh_CN@collation=stroke"] {
+            let loc = ULoc::try_from(*id)?;
+            let name = loc.to_windows_locale_name()?;
+            assert_eq!(ULoc::from_windows_locale_name(&name)?, loc, "id: {}", id);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_for_language_tag_grandfathered() -> Result<(), Error> {
+        for (tag, expected) in &[
+            ("i-klingon", "tlh"),
+            ("zh-min-nan", "nan"),
+            ("art-lojban", "jbo"),
+            ("en-GB-oed", "en_GB_OXENDICT"),
+            ("sgn-BE-FR", "sfb"),
+            ("i-default", "en@x=i-default"),
+        ] {
+            assert_eq!(
+                ULoc::for_language_tag(tag)?.label(),
+                *expected,
+                "tag: {}",
+                tag
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_for_language_tag_malformed() {
+        for tag in &["en-US-$$", "en--US", "toolongtoolong-US"] {
+            let error = ULoc::for_language_tag(tag).expect_err(tag);
+            assert_eq!(
+                error.error_code(),
+                Some(UErrorCode::U_ILLEGAL_ARGUMENT_ERROR),
+                "tag: {}",
+                tag
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_from_agrees_with_canonicalize() -> Result<(), Error> {
+        for tag in &[
+            "en-US",
+            "EN_us",
+            "de_DE@collation=phonebook",
+            "zh-Hant-TW",
+            "und",
+        ] {
+            let uncached = ULoc {
+                repr: tag.to_string(),
+            }
+            .canonicalize()?;
+            // The second conversion is answered from the cache, if enabled.
+            assert_eq!(ULoc::try_from(*tag)?, uncached);
+            assert_eq!(ULoc::try_from(*tag)?, uncached);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_trims_whitespace() -> Result<(), Error> {
+        let expected = ULoc::try_from("en_US")?;
+        for id in &[
+            " en-US\n",
+            "\ten_US ",
+            "\u{feff}en-US",
+            "\u{feff} en-US\r\n",
+        ] {
+            assert_eq!(ULoc::try_from(*id)?, expected, "id: {:?}", id);
+        }
+        assert_eq!(ULoc::try_from(Cow::Owned(" en-US ".to_string()))?, expected);
+        // Interior whitespace and byte order marks are left to ICU.
+        assert_ne!(ULoc::try_from("en US")?, expected);
+        assert_ne!(ULoc::try_from("en_\u{feff}US")?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_cow() -> Result<(), Error> {
+        let expected = ULoc::try_from("en_US")?;
+        assert_eq!(ULoc::try_from(Cow::Borrowed("en-US"))?, expected);
+        assert_eq!(ULoc::try_from(Cow::Owned("en-US".to_string()))?, expected);
+        let owned: Cow<str> = Cow::Owned("en@=x".to_string());
+        assert!(ULoc::try_from(owned).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "canonicalize_cache")]
+    #[test]
+    fn test_canonicalize_cache() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr-latn-rs")?;
+        assert_eq!(
+            canonicalize_cache::get("sr-latn-rs"),
+            Some(loc.repr.clone())
+        );
+        for i in 0..=canonicalize_cache::CAPACITY {
+            canonicalize_cache::insert(&format!("tag{}", i), "und");
+        }
+        assert_eq!(ULoc::try_from("sr-latn-rs")?, loc);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> Result<(), Error> {
+        let loc: ULoc = "sr-Latn-RS".parse()?;
+        assert_eq!(loc, ULoc::try_from("sr_Latn_RS")?);
+
+        let error = "en@calendar=".parse::<ULoc>().unwrap_err();
+        assert!(format!("{}", error).contains("\"en@calendar=\""));
+        assert_eq!(error.error_code(), Some(UErrorCode::U_INVALID_FORMAT_ERROR));
+        let error = ULoc::try_from("en@=x").unwrap_err();
+        assert!(format!("{}", error).contains("\"en@=x\""));
+        Ok(())
+    }
+
+    #[cfg(feature = "icu_locid-interop")]
+    #[test]
+    fn test_icu_locid_interop() -> Result<(), Error> {
+        let loc = ULoc::try_from("zh_Hant_TW@calendar=roc")?;
+        let locale = icu_locid::Locale::try_from(&loc)?;
+        assert_eq!(locale.to_string(), "zh-Hant-TW-u-ca-roc");
+        assert_eq!(ULoc::try_from(&locale)?, loc);
+
+        let locale: icu_locid::Locale = "zh-Hant-TW".parse().unwrap();
+        assert_eq!(ULoc::try_from(&locale)?, ULoc::try_from("zh_Hant_TW")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_for_language_tag_lenient() -> Result<(), Error> {
+        let expected = ULoc::try_from("en_US")?;
+        assert_eq!(ULoc::for_language_tag_lenient("en_us")?, expected);
+        assert_eq!(ULoc::for_language_tag_lenient(" en-US ")?, expected);
+        assert_eq!(ULoc::for_language_tag_lenient("\ten_US\n")?, expected);
+        assert_eq!(
+            ULoc::for_language_tag_lenient("sr_Cyrl_RS")?,
+            ULoc::try_from("sr_Cyrl_RS")?
+        );
+        assert!(ULoc::for_language_tag("en_us").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bcp47_strict() -> Result<(), Error> {
+        assert_eq!(ULoc::parse_bcp47_strict("en-US")?, ULoc::try_from("en_US")?);
+        let error = ULoc::parse_bcp47_strict("en_US@calendar=japanese").unwrap_err();
+        assert_eq!(error.error_code(), None);
+        assert!(format!("{}", error).contains("not a BCP-47 language tag"));
+        assert!(ULoc::parse_bcp47_strict("en-US-u-ca-japanese").is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_bounded() -> Result<(), Error> {
+        assert_eq!(
+            ULoc::try_from_bounded("en-US", 16)?,
+            ULoc::try_from("en_US")?
+        );
+        assert_eq!(
+            ULoc::try_from_bounded("en_US", 5)?,
+            ULoc::try_from("en_US")?
+        );
+        let error = ULoc::try_from_bounded(&"x".repeat(10_000), 64).unwrap_err();
+        assert!(format!("{}", error).contains("longer than the limit of 64 bytes"));
+        assert!(ULoc::try_from_bounded("en_US", 4).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_language_tag_into() -> Result<(), Error> {
+        let mut out = String::new();
+        for (id, tag) in &[
+            ("sr_Cyrl_RS@calendar=japanese", "sr-Cyrl-RS-u-ca-japanese"),
+            ("en_US", "en-US"),
+            ("zh_Hant_TW@collation=stroke", "zh-Hant-TW-u-co-stroke"),
+            ("", "und"),
+        ] {
+            ULoc::try_from(*id)?.to_language_tag_into(true, &mut out)?;
+            assert_eq!(out, *tag);
+        }
+        let capacity = out.capacity();
+        ULoc::try_from("de")?.to_language_tag_into(false, &mut out)?;
+        assert_eq!(out, "de");
+        assert_eq!(out.capacity(), capacity);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_language_tag_bytes() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr_Cyrl_RS@calendar=japanese")?;
+        for strict in &[false, true] {
+            assert_eq!(
+                loc.to_language_tag_bytes(*strict)?,
+                loc.to_language_tag(*strict)?.into_bytes()
+            );
+        }
+        assert_eq!(
+            loc.to_language_tag_bytes(true)?,
+            b"sr-Cyrl-RS-u-ca-japanese"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parent() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr_Latn_RS")?;
+        assert_eq!(
+            loc.fallback_iter().collect::<Vec<_>>(),
+            locales_from_tags(vec!["sr_Latn_RS", "sr_Latn", "sr", ""])?
+        );
+        assert_eq!(ULoc::try_from("")?.parent(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ancestors_until() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr_Latn_RS")?;
+        assert_eq!(
+            loc.ancestors_until(&ULoc::try_from("sr")?),
+            locales_from_tags(vec!["sr_Latn_RS", "sr_Latn", "sr"])?
+        );
+        assert_eq!(
+            loc.ancestors_until(&loc),
+            locales_from_tags(vec!["sr_Latn_RS"])?
+        );
+        assert_eq!(
+            loc.ancestors_until(&ULoc::try_from("hr")?),
+            locales_from_tags(vec!["sr_Latn_RS", "sr_Latn", "sr", ""])?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fallback_chain_with_default() -> Result<(), Error> {
+        let chain = ULoc::try_from("de_CH")?.fallback_chain_with_default(&ULoc::try_from("en_US")?);
+        assert_eq!(
+            chain,
+            locales_from_tags(vec!["de_CH", "de", "en_US", "en", ""])?
+        );
+
+        let chain = ULoc::try_from("en_GB")?.fallback_chain_with_default(&ULoc::try_from("en_US")?);
+        assert_eq!(chain, locales_from_tags(vec!["en_GB", "en", "en_US", ""])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_keywords_reversed() -> Result<(), Error> {
+        let loc = ULoc::try_from("en@numbers=arab;collation=phonebook;calendar=japanese")?;
+        assert_eq!(
+            loc.keywords().rev().collect::<Vec<String>>(),
+            vec!["numbers", "collation", "calendar"]
         );
-        ptr.as_ref().map(|ptr| ffi::CStr::from_ptr(ptr).to_owned())
-    };
-    unicode_value.map(|cstring| cstring_to_string(&cstring))
-}
+        Ok(())
+    }
 
-/// Implements `uloc_toLegacyKey` from ICU4C.
-pub fn to_legacy_key(unicode_keyword: &str) -> Option<String> {
-    let unicode_keyword = str_to_cstring(unicode_keyword);
-    let legacy_keyword: Option<ffi::CString> = unsafe {
-        let ptr = versioned_function!(uloc_toLegacyKey)(unicode_keyword.as_ptr());
-        ptr.as_ref().map(|ptr| ffi::CStr::from_ptr(ptr).to_owned())
-    };
-    legacy_keyword.map(|cstring| cstring_to_string(&cstring))
-}
+    #[test]
+    fn test_split_extensions() -> Result<(), Error> {
+        let loc = ULoc::for_language_tag("en-US-u-ca-gregory-x-private")?;
+        let (base, extensions) = loc.split_extensions()?;
+        assert_eq!(base, ULoc::try_from("en_US")?);
+        let expected: BTreeMap<char, String> = vec![
+            ('u', "ca-gregory".to_string()),
+            ('x', "private".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(extensions, expected);
 
-/// Infallibly converts a Rust string to a `CString`. If there's an interior NUL, the string is
-/// truncated up to that point.
-fn str_to_cstring(input: &str) -> ffi::CString {
-    ffi::CString::new(input)
-        .unwrap_or_else(|e| ffi::CString::new(&input[0..e.nul_position()]).unwrap())
-}
+        let (base, extensions) = ULoc::try_from("sr_Latn")?.split_extensions()?;
+        assert_eq!(base, ULoc::try_from("sr_Latn")?);
+        assert!(extensions.is_empty());
+        Ok(())
+    }
 
-/// Infallibly converts a `CString` to a Rust `String`. We can safely assume that any strings
-/// coming from ICU data are valid UTF-8.
-fn cstring_to_string(input: &ffi::CString) -> String {
-    input.to_string_lossy().to_string()
-}
+    #[test]
+    fn test_from_base_and_extensions() -> Result<(), Error> {
+        for tag in &[
+            "en-US-u-ca-gregory-x-private",
+            "de-t-en-u-co-phonebk",
+            "sr-Latn",
+        ] {
+            let loc = ULoc::for_language_tag(tag)?;
+            let (base, extensions) = loc.split_extensions()?;
+            assert_eq!(ULoc::from_base_and_extensions(&base, &extensions)?, loc);
+        }
 
-#[cfg(test)]
-mod tests {
-    use {super::*, anyhow::Error};
+        let mut extensions = BTreeMap::new();
+        extensions.insert('x', "private".to_string());
+        extensions.insert('u', "nu-thai".to_string());
+        let loc = ULoc::from_base_and_extensions(&ULoc::try_from("th_TH")?, &extensions)?;
+        assert_eq!(loc.to_language_tag(true)?, "th-TH-u-nu-thai-x-private");
+        Ok(())
+    }
 
     #[test]
-    fn test_language() -> Result<(), Error> {
-        let loc = ULoc::try_from("es-CO")?;
-        assert_eq!(loc.language(), Some("es".to_string()));
+    fn test_numbering_system() -> Result<(), Error> {
+        assert_eq!(ULoc::try_from("ar")?.numbering_system()?, "arab");
+        assert_eq!(ULoc::try_from("en")?.numbering_system()?, "latn");
+        assert_eq!(
+            ULoc::try_from("hi_IN@numbers=deva")?.numbering_system()?,
+            "deva"
+        );
+        assert_eq!(
+            ULoc::try_from("ar_EG@numbers=latn")?.numbering_system()?,
+            "latn"
+        );
         Ok(())
     }
 
     #[test]
-    fn test_language_absent() -> Result<(), Error> {
-        let loc = ULoc::for_language_tag("und-CO")?;
-        assert_eq!(loc.language(), None);
+    fn test_separators() -> Result<(), Error> {
+        let en_us = ULoc::try_from("en_US")?;
+        assert_eq!(en_us.decimal_separator()?, ".");
+        assert_eq!(en_us.grouping_separator()?, ",");
+        let de_de = ULoc::try_from("de_DE")?;
+        assert_eq!(de_de.decimal_separator()?, ",");
+        assert_eq!(de_de.grouping_separator()?, ".");
         Ok(())
     }
 
     #[test]
-    fn test_script() -> Result<(), Error> {
-        let loc = ULoc::try_from("sr-Cyrl")?;
-        assert_eq!(loc.script(), Some("Cyrl".to_string()));
+    fn test_date_pattern() -> Result<(), Error> {
+        assert_eq!(ULoc::try_from("en")?.date_pattern("yMMMd")?, "MMM d, y");
+        assert_eq!(ULoc::try_from("de")?.date_pattern("yMMMd")?, "d. MMM y");
         Ok(())
     }
 
     #[test]
-    fn test_script_absent() -> Result<(), Error> {
-        let loc = ULoc::try_from("sr")?;
-        assert_eq!(loc.script(), None);
+    fn test_first_day_of_week() -> Result<(), Error> {
+        use sys::UCalendarDaysOfWeek::*;
+        let cases = [
+            ("en_US", UCAL_SUNDAY),
+            ("fr_FR", UCAL_MONDAY),
+            ("fa_IR", UCAL_SATURDAY),
+        ];
+        for (tag, day) in &cases {
+            assert_eq!(ULoc::try_from(*tag)?.first_day_of_week()?, *day, "{}", tag);
+        }
         Ok(())
     }
 
     #[test]
-    fn test_country() -> Result<(), Error> {
-        let loc = ULoc::try_from("es-CO")?;
-        assert_eq!(loc.country(), Some("CO".to_string()));
+    fn test_available_calendars() -> Result<(), Error> {
+        let ja_jp = ULoc::try_from("ja_JP")?.available_calendars()?;
+        assert!(ja_jp.contains(&"japanese".to_string()), "{:?}", ja_jp);
+        let en_us = ULoc::try_from("en_US")?.available_calendars()?;
+        assert_eq!(en_us.first().map(String::as_str), Some("gregorian"));
+        let th_th = ULoc::try_from("th_TH")?.available_calendars()?;
+        assert_eq!(th_th.first().map(String::as_str), Some("buddhist"));
         Ok(())
     }
 
     #[test]
-    fn test_country_absent() -> Result<(), Error> {
-        let loc = ULoc::try_from("es")?;
-        assert_eq!(loc.country(), None);
+    fn test_language_tag_or_label() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr_Cyrl_RS")?;
+        assert_eq!(loc.language_tag_or_label(), "sr-Cyrl-RS");
+        let loc = ULoc::try_from("en_US_X")?;
+        assert!(loc.to_language_tag(true).is_err());
+        assert_eq!(loc.language_tag_or_label(), "en_US_X");
         Ok(())
     }
 
-    // This test yields a different result in ICU versions prior to 64:
-    // "zh-Latn@collation=pinyin".
-    #[cfg(features = "icu_version_64_plus")]
     #[test]
-    fn test_variant() -> Result<(), Error> {
-        let loc = ULoc::try_from("zh-Latn-pinyin")?;
-        assert_eq!(
-            loc.variant(),
-            Some("PINYIN".to_string()),
-            "locale was: {:?}",
-            loc
-        );
+    fn test_opentype_language_tag() -> Result<(), Error> {
+        let cases = [
+            ("de", Some("DEU")),
+            ("en", Some("ENG")),
+            ("en_GB", Some("ENG")),
+            ("zh_CN", Some("ZHS")),
+            ("zh_TW", Some("ZHT")),
+            ("haw", None),
+        ];
+        for (tag, expected) in &cases {
+            assert_eq!(
+                ULoc::try_from(*tag)?.opentype_language_tag().as_deref(),
+                *expected,
+                "for {}",
+                tag
+            );
+        }
         Ok(())
     }
 
     #[test]
-    fn test_variant_absent() -> Result<(), Error> {
-        let loc = ULoc::try_from("zh-Latn")?;
-        assert_eq!(loc.variant(), None);
+    fn test_is_ascii_id() -> Result<(), Error> {
+        assert!(ULoc::try_from("sr_Latn_RS@calendar=japanese")?.is_ascii_id());
+        assert!(ULoc::for_language_tag("de-CH-u-co-phonebk")?.is_ascii_id());
+        assert!(!ULoc::try_from("en_US_ü")?.is_ascii_id());
+        let c_str = ffi::CString::new("en@calendar=é")?;
+        assert!(!ULoc::try_from(c_str.as_c_str())?.is_ascii_id());
         Ok(())
     }
 
     #[test]
-    fn test_default_locale() {
-        let loc = ULoc::try_from("fr-fr").expect("get fr_FR locale");
-        set_default(&loc).expect("successful set of locale");
-        assert_eq!(get_default().label(), loc.label());
-        assert_eq!(loc.label(), "fr_FR", "The locale should get canonicalized");
-        let loc = ULoc::try_from("en-us").expect("get en_US locale");
-        set_default(&loc).expect("successful set of locale");
-        assert_eq!(get_default().label(), loc.label());
+    fn test_id_with_dashes() -> Result<(), Error> {
+        let loc = ULoc::try_from("en_US@calendar=japanese")?;
+        assert_eq!(loc.id_with_dashes(), "en-US@calendar=japanese");
+        let loc = ULoc::try_from("sr_Latn_RS")?;
+        assert_eq!(loc.id_with_dashes(), "sr-Latn-RS");
+        let loc = ULoc::try_from("de@collation=phonebook_x")?;
+        assert_eq!(loc.id_with_dashes(), "de@collation=phonebook_x");
+        Ok(())
     }
 
     #[test]
-    fn test_add_likely_subtags() {
-        let loc = ULoc::try_from("en-US").expect("get en_US locale");
-        let with_likely_subtags = loc.add_likely_subtags().expect("should add likely subtags");
-        let expected = ULoc::try_from("en_Latn_US").expect("get en_Latn_US locale");
-        assert_eq!(with_likely_subtags.label(), expected.label());
+    fn test_for_each_keyword() -> Result<(), Error> {
+        let loc = ULoc::try_from("de_DE@currency=EUR;collation=phonebook")?;
+        let mut pairs = vec![];
+        loc.for_each_keyword(|keyword, value| {
+            pairs.push(format!("{}={}", keyword, value));
+            Ok(())
+        })?;
+        assert_eq!(pairs, vec!["collation=phonebook", "currency=EUR"]);
+
+        let mut calls = 0;
+        let result = loc.for_each_keyword(|_, _| {
+            calls += 1;
+            Err(common::Error::Sys(
+                sys::UErrorCode::U_ILLEGAL_ARGUMENT_ERROR,
+            ))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+        Ok(())
     }
 
     #[test]
-    fn test_minimize_subtags() {
-        let loc = ULoc::try_from("sr_Cyrl_RS").expect("get sr_Cyrl_RS locale");
-        let minimized_subtags = loc.minimize_subtags().expect("should minimize subtags");
-        let expected = ULoc::try_from("sr").expect("get sr locale");
-        assert_eq!(minimized_subtags.label(), expected.label());
+    fn test_unicode_extension_keywords() -> Result<(), Error> {
+        let loc = ULoc::for_language_tag("en-US-u-ca-gregory-nu-latn")?;
+        assert_eq!(loc.label(), "en_US@calendar=gregorian;numbers=latn");
+        let expected: BTreeMap<String, String> = vec![("ca", "gregory"), ("nu", "latn")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(loc.unicode_extension_keywords()?, expected);
+        assert!(ULoc::try_from("en_US")?
+            .unicode_extension_keywords()?
+            .is_empty());
+        Ok(())
     }
 
     #[test]
-    fn test_to_language_tag() {
-        let loc = ULoc::try_from("sr_Cyrl_RS").expect("get sr_Cyrl_RS locale");
-        let language_tag = loc
-            .to_language_tag(true)
-            .expect("should convert to language tag");
-        assert_eq!(language_tag, "sr-Cyrl-RS".to_string());
+    fn test_legacy_charset() -> Result<(), Error> {
+        let cases = [
+            ("ja_JP", Some("Shift_JIS")),
+            ("ru_RU", Some("windows-1251")),
+            ("de_CH", Some("windows-1252")),
+            ("zh_TW", Some("Big5")),
+            ("zh_CN", Some("GBK")),
+            ("sr_Latn_RS", Some("windows-1250")),
+            ("sr_RS", Some("windows-1251")),
+            ("hi_IN", None),
+        ];
+        for (tag, expected) in &cases {
+            assert_eq!(
+                ULoc::try_from(*tag)?.legacy_charset()?.as_deref(),
+                *expected,
+                "for {}",
+                tag
+            );
+        }
+        Ok(())
     }
 
     #[test]
@@ -562,6 +5443,37 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_keywords_sorted() -> Result<(), Error> {
+        let expected = vec![
+            "calendar".to_string(),
+            "collation".to_string(),
+            "numbers".to_string(),
+        ];
+
+        let loc = ULoc::try_from("de@numbers=latn;collation=phonebook;calendar=buddhist")?;
+        assert_eq!(loc.keywords().collect::<Vec<String>>(), expected);
+        assert_eq!(
+            loc.keywords_map()?.keys().cloned().collect::<Vec<String>>(),
+            expected
+        );
+
+        let loc = ULoc::for_language_tag("de-u-nu-latn-co-phonebk-ca-buddhist")?;
+        assert_eq!(loc.keywords().collect::<Vec<String>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_keywords_map() -> Result<(), Error> {
+        let loc = ULoc::for_language_tag("az-Cyrl-AZ-u-ca-hebrew-nu-deva")?;
+        let mut expected = BTreeMap::new();
+        expected.insert("calendar".to_string(), "hebrew".to_string());
+        expected.insert("numbers".to_string(), "deva".to_string());
+        assert_eq!(loc.keywords_map()?, expected);
+        assert!(ULoc::try_from("az_Cyrl_AZ")?.keywords_map()?.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn test_keywords_empty() -> Result<(), Error> {
         let loc = ULoc::for_language_tag("az-Cyrl-AZ")?;
@@ -602,6 +5514,137 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_keyword_value_into() -> Result<(), Error> {
+        let loc = ULoc::try_from("en@calendar=japanese;collation=phonebook")?;
+        let mut buf = Vec::new();
+        assert_eq!(loc.keyword_value_into("calendar", &mut buf)?, Some(8));
+        assert_eq!(buf, b"japanese");
+        let capacity = buf.capacity();
+        assert_eq!(loc.keyword_value_into("collation", &mut buf)?, Some(9));
+        assert_eq!(buf, b"phonebook");
+        assert_eq!(loc.keyword_value_into("currency", &mut buf)?, None);
+        assert!(buf.is_empty());
+        assert_eq!(buf.capacity(), capacity);
+
+        let mut small = Vec::with_capacity(2);
+        assert_eq!(loc.keyword_value_into("collation", &mut small)?, Some(9));
+        assert_eq!(small, b"phonebook");
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_keyword_value() -> Result<(), Error> {
+        let loc = ULoc::try_from("en_US@calendar=japanese")?;
+        assert_eq!(
+            loc.set_keyword_value("collation", Some("phonebook"))?,
+            ULoc::try_from("en_US@calendar=japanese;collation=phonebook")?
+        );
+        assert_eq!(
+            loc.set_keyword_value("calendar", Some("buddhist"))?,
+            ULoc::try_from("en_US@calendar=buddhist")?
+        );
+        assert_eq!(
+            loc.set_keyword_value("calendar", None)?,
+            ULoc::try_from("en_US")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_keyword_names_case_insensitive() -> Result<(), Error> {
+        let loc = ULoc::try_from("en_US")?.set_keyword_value("calendar", Some("japanese"))?;
+        for name in &["calendar", "Calendar", "CALENDAR"] {
+            assert_eq!(loc.keyword_value(name)?.as_deref(), Some("japanese"));
+            let mut buf = vec![];
+            assert_eq!(loc.keyword_value_into(name, &mut buf)?, Some(8));
+        }
+        let mixed = ULoc::try_from("en_US")?.set_keyword_value("CaLeNdAr", Some("japanese"))?;
+        assert_eq!(mixed, loc);
+        assert_eq!(
+            loc.set_keyword_value("Calendar", None)?,
+            ULoc::try_from("en_US")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_clear_all_keywords() -> Result<(), Error> {
+        let loc = ULoc::try_from("sr_Latn_RS_REVISED@calendar=japanese;collation=phonebook")?;
+        assert_eq!(loc.keywords().count(), 2);
+        let cleared = loc.clear_all_keywords()?;
+        assert_eq!(cleared.keywords().count(), 0);
+        assert_eq!(cleared, ULoc::try_from("sr_Latn_RS_REVISED")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_keyword_diff() -> Result<(), Error> {
+        let before = ULoc::try_from("en@ca=gregory")?;
+        let after = ULoc::try_from("en@ca=japanese;nu=latn")?;
+        let set = |keywords: &[&str]| keywords.iter().map(|k| k.to_string()).collect();
+        assert_eq!(
+            before.keyword_diff(&after)?,
+            KeywordDiff {
+                added: set(&["nu"]),
+                removed: set(&[]),
+                changed: set(&["ca"]),
+            }
+        );
+        assert_eq!(
+            after.keyword_diff(&before)?,
+            KeywordDiff {
+                added: set(&[]),
+                removed: set(&["nu"]),
+                changed: set(&["ca"]),
+            }
+        );
+        assert_eq!(before.keyword_diff(&before)?, KeywordDiff::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_keywords_from() -> Result<(), Error> {
+        let user = ULoc::try_from("de_CH@calendar=gregorian;collation=phonebook")?;
+        let defaults = ULoc::try_from("en_US@calendar=buddhist;numbers=latn")?;
+        assert_eq!(
+            user.with_keywords_from(&defaults, false)?,
+            ULoc::try_from("de_CH@calendar=gregorian;collation=phonebook;numbers=latn")?
+        );
+        assert_eq!(
+            user.with_keywords_from(&defaults, true)?,
+            ULoc::try_from("de_CH@calendar=buddhist;collation=phonebook;numbers=latn")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_keywords() -> Result<(), Error> {
+        let loc = ULoc::try_from("en@calendar=islamicc;timezone=US/Eastern;collation=phonebook")?;
+        assert_eq!(
+            loc.migrate_keywords()?,
+            ULoc::try_from(
+                "en@calendar=islamic-civil;collation=phonebook;timezone=America/New_York"
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_keyword() -> Result<(), Error> {
+        let loc = ULoc::try_from("de@collation=phonebook")?;
+        assert!(loc.has_keyword("collation")?);
+        assert!(!loc.has_keyword("calendar")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_keyword_no_keywords() -> Result<(), Error> {
+        let loc = ULoc::try_from("de")?;
+        assert!(!loc.has_keyword("collation")?);
+        Ok(())
+    }
+
     #[test]
     fn test_unicode_keyword_value() -> Result<(), Error> {
         let loc = ULoc::for_language_tag("az-Cyrl-AZ-u-ca-hebrew-fw-sunday-nu-deva-tz-usnyc")?;
@@ -735,6 +5778,113 @@ mod tests {
         assert_eq!(actual, (None, UAcceptResult::ULOC_ACCEPT_FAILED))
     }
 
+    #[test]
+    fn test_available_locales() -> Result<(), Error> {
+        let locales = available_locales();
+        assert!(locales.contains(&ULoc::try_from("en_US")?));
+        assert!(locales.contains(&ULoc::try_from("sr_Cyrl_RS")?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_available_locales_with_language() -> Result<(), Error> {
+        let locales: Vec<ULoc> = available_locales_with_language("en").collect();
+        assert!(locales.contains(&ULoc::try_from("en")?));
+        assert!(locales.contains(&ULoc::try_from("en_US")?));
+        assert!(locales.contains(&ULoc::try_from("en_GB")?));
+        for uloc in &locales {
+            assert_eq!(
+                uloc.language(),
+                Some("en".to_string()),
+                "locale: {:?}",
+                uloc
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_accept_language_borrowed_locales() -> Result<(), Error> {
+        let accept_list = locales_from_tags(vec!["es_MX", "ar_EG", "fr_FR"])?;
+        let available_locales = locales_from_tags(vec!["de_DE", "en_US", "es", "nl_NL"])?;
+
+        let borrowed = accept_language(&accept_list, &available_locales)?;
+        let owned = accept_language(accept_list, available_locales)?;
+        assert_eq!(borrowed, owned);
+        assert_eq!(
+            borrowed,
+            (
+                ULoc::try_from("es").ok(),
+                UAcceptResult::ULOC_ACCEPT_FALLBACK
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_locales_from_tags() -> Result<(), Error> {
+        let locales = locales_from_tags(vec!["en-US", "sr_Cyrl"])?;
+        assert_eq!(
+            locales,
+            vec![ULoc::try_from("en_US")?, ULoc::try_from("sr_Cyrl")?]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_negotiate_first_stage() -> Result<(), Error> {
+        let desired = vec![ULoc::try_from("es_MX")?, ULoc::try_from("fr_FR")?];
+        let supported = vec![ULoc::try_from("de_DE")?, ULoc::try_from("es")?];
+        assert_eq!(negotiate(&desired, &supported), Some(ULoc::try_from("es")?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_negotiate_second_stage() -> Result<(), Error> {
+        let desired = vec![ULoc::try_from("und_Latn_RS")?];
+        let supported = vec![ULoc::try_from("de_DE")?, ULoc::try_from("sr_Latn")?];
+
+        // The first stage alone does not find the match implied by likely subtags.
+        let (matched, _) = accept_language(&desired, &supported)?;
+        assert_eq!(matched, None);
+
+        assert_eq!(
+            negotiate(&desired, &supported),
+            Some(ULoc::try_from("sr_Latn")?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_negotiate_script_implied() -> Result<(), Error> {
+        let desired = vec![ULoc::try_from("zh-TW")?];
+        let supported = vec![ULoc::try_from("zh-Hans")?, ULoc::try_from("zh-Hant")?];
+
+        // Since ICU 67, the first stage already knows that zh_TW implies Hant.
+        #[cfg(feature = "icu_version_67_plus")]
+        assert_eq!(
+            accept_language(&desired, &supported)?,
+            (
+                Some(ULoc::try_from("zh-Hant")?),
+                UAcceptResult::ULOC_ACCEPT_FALLBACK
+            )
+        );
+
+        assert_eq!(
+            negotiate(&desired, &supported),
+            Some(ULoc::try_from("zh-Hant")?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_negotiate_no_match() -> Result<(), Error> {
+        let desired = vec![ULoc::try_from("es_ES")?, ULoc::try_from("ar_EG")?];
+        let supported = vec![ULoc::try_from("el_GR")?];
+        assert_eq!(negotiate(&desired, &supported), None);
+        Ok(())
+    }
+
     #[test]
     fn test_to_unicode_locale_key() -> Result<(), Error> {
         let actual = to_unicode_locale_key("calendar");
@@ -749,6 +5899,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_canonical_keyword() -> Result<(), Error> {
+        for key in &["calendar", "ca", "Calendar", "CA"] {
+            assert_eq!(canonical_keyword(key)?, "calendar");
+        }
+        for key in &["colnumeric", "kn"] {
+            assert_eq!(canonical_keyword(key)?, "colnumeric");
+        }
+        assert_eq!(canonical_keyword("tz")?, "timezone");
+        assert_eq!(canonical_keyword("Foo")?, "foo");
+        assert!(canonical_keyword("").is_err());
+        assert!(canonical_keyword("not a key").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_keyword_values() -> Result<(), Error> {
+        let calendars = keyword_values("ca")?.collect::<Result<Vec<_>, _>>()?;
+        assert!(calendars.contains(&"gregorian".to_string()));
+        assert!(calendars.contains(&"japanese".to_string()));
+        let collations = keyword_values("collation")?.collect::<Result<Vec<_>, _>>()?;
+        assert!(collations.contains(&"phonebook".to_string()));
+        assert!(keyword_values("timezone").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_valid_keyword_value() {
+        assert!(is_valid_keyword_value("calendar", "japanese"));
+        assert!(is_valid_keyword_value("ca", "gregory"));
+        assert!(is_valid_keyword_value("co", "phonebk"));
+        assert!(!is_valid_keyword_value("calendar", "nonsense"));
+        assert!(!is_valid_keyword_value("timezone", "utc"));
+        assert!(!is_valid_keyword_value("", "japanese"));
+    }
+
     #[test]
     fn test_to_legacy_key() -> Result<(), Error> {
         let actual = to_legacy_key("ca");
@@ -756,6 +5942,15 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_to_legacy_type() -> Result<(), Error> {
+        let actual = to_legacy_type("ca", "islamic-civil");
+        assert_eq!(actual, Some("islamic-civil".to_string()));
+        let actual = to_legacy_type("co", "phonebk");
+        assert_eq!(actual, Some("phonebook".to_string()));
+        Ok(())
+    }
+
     #[test]
     fn test_str_to_cstring() -> Result<(), Error> {
         assert_eq!(str_to_cstring("abc"), ffi::CString::new("abc")?);